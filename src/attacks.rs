@@ -0,0 +1,101 @@
+use crate::{board::Board, square::Square, pieces::{Color, PieceKind}, bitboard};
+
+// How many pieces of one colour attack each square
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AttackMap {
+    counts: [u8; 64],
+}
+
+impl AttackMap {
+    pub fn get_count(&self, square: Square) -> u32 {
+        self.counts[square as usize] as u32
+    }
+
+    pub fn is_attacked(&self, square: Square) -> bool {
+        self.counts[square as usize] > 0
+    }
+
+    // Every square attacked at least once, as a bitboard
+    pub fn get_attacked(&self) -> u64 {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|x| *x.1 > 0)
+            .fold(0, |attacked, (index, _)| attacked | 1 << index)
+    }
+}
+
+impl Board {
+    // The pieces of `color` attacking a square, as a bitboard
+    pub fn attackers_of(&self, square: Square, color: Color) -> u64 {
+        self.get_attackers(square, color, self.get_all_occupancy())
+    }
+
+    pub fn is_attacked(&self, square: Square, color: Color) -> bool {
+        self.attackers_of(square, color) != 0
+    }
+
+    // Attackers on the board as it would stand with another occupancy, such as after a move not yet played
+    pub(crate) fn get_attackers(&self, square: Square, color: Color, occupancy: u64) -> u64 {
+        let queens = self.get_pieces(color, PieceKind::Queen);
+
+        // A square is attacked by every piece it would attack if it held that piece itself
+        bitboard::knight_attacks(square) & self.get_pieces(color, PieceKind::Knight) |
+            bitboard::king_attacks(square) & self.get_pieces(color, PieceKind::King) |
+            bitboard::pawn_attacks(square, color.opposite()) & self.get_pieces(color, PieceKind::Pawn) |
+            bitboard::bishop_attacks(square, occupancy) & (self.get_pieces(color, PieceKind::Bishop) | queens) |
+            bitboard::rook_attacks(square, occupancy) & (self.get_pieces(color, PieceKind::Rook) | queens)
+    }
+
+    // Pieces behind another attacker on the same line are not counted until it moves away
+    pub fn get_attack_map(&self, color: Color) -> AttackMap {
+        let occupancy = self.get_all_occupancy();
+        let mut counts = [0; 64];
+
+        for coord in bitboard::squares(self.get_occupancy(color)) {
+            let attacks = match self.get_piece(coord).map(|x| x.get_kind()) {
+                Some(PieceKind::Pawn) => bitboard::pawn_attacks(coord, color),
+                Some(PieceKind::Knight) => bitboard::knight_attacks(coord),
+                Some(PieceKind::Bishop) => bitboard::bishop_attacks(coord, occupancy),
+                Some(PieceKind::Rook) => bitboard::rook_attacks(coord, occupancy),
+                Some(PieceKind::Queen) => bitboard::queen_attacks(coord, occupancy),
+                Some(PieceKind::King) => bitboard::king_attacks(coord),
+                None => 0,
+            };
+
+            for square in bitboard::squares(attacks) {
+                counts[square as usize] += 1;
+            }
+        }
+
+        AttackMap { counts }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{board::Board, square::Square, pieces::Color, bitboard};
+
+    #[test]
+    fn attackers_of_a_square() {
+        let board = Board::from_fen("4k3/8/8/3p4/5N2/1B6/8/R3K3 w - - 0 1").unwrap();
+        let attackers = bitboard::squares(board.attackers_of(Square::D5, Color::White)).collect::<Vec<Square>>();
+
+        assert_eq!(attackers, vec![Square::B3, Square::F4]);
+        assert!(board.is_attacked(Square::A8, Color::White));
+        assert!(board.is_attacked(Square::E4, Color::Black));
+        assert!(!board.is_attacked(Square::D4, Color::Black));
+    }
+
+    #[test]
+    fn attack_map_counts_each_attacker() {
+        let board = Board::from_fen("4k3/8/8/3p4/5N2/1B6/8/R3K3 w - - 0 1").unwrap();
+        let attacks = board.get_attack_map(Color::White);
+
+        assert_eq!(attacks.get_count(Square::D5), 2);
+        assert_eq!(attacks.get_count(Square::D1), 3);
+        assert_eq!(attacks.get_count(Square::H8), 0);
+        assert!(attacks.get_attacked() & bitboard::square_bit(Square::A8) != 0);
+        assert!(!attacks.is_attacked(Square::E4));
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{pieces::Color, square::Square};
+
+// Bits follow the numbering of `Square`, file by file: one step up the board is +1, one step right is +8
+
+const KNIGHT_OFFSETS: [(i32, i32); 8] = [
+    ( 2,  1), ( 2, -1), (-2,  1), (-2, -1),
+    ( 1,  2), ( 1, -2), (-1,  2), (-1, -2),
+];
+
+const KING_OFFSETS: [(i32, i32); 8] = [
+    (-1, -1), (-1, 0), (-1, 1),
+    ( 0, -1),          ( 0, 1),
+    ( 1, -1), ( 1, 0), ( 1, 1)
+];
+
+const DIRECTIONS: [(i32, i32); 8] = [
+    ( 0,  1), ( 1,  1), ( 1,  0), ( 1, -1),
+    ( 0, -1), (-1, -1), (-1,  0), (-1,  1),
+];
+
+const DIAGONALS: [usize; 4] = [1, 3, 5, 7];
+const LINES: [usize; 4] = [0, 2, 4, 6];
+
+pub const KNIGHT_ATTACKS: [u64; 64] = leaper_attacks(&KNIGHT_OFFSETS);
+pub const KING_ATTACKS: [u64; 64] = leaper_attacks(&KING_OFFSETS);
+pub const PAWN_ATTACKS: [[u64; 64]; 2] = [
+    leaper_attacks(&[(-1, 1), (1, 1)]),
+    leaper_attacks(&[(-1, -1), (1, -1)]),
+];
+
+const RAYS: [[u64; 64]; 8] = rays();
+
+// The light squares, such as h1 and a8
+pub const LIGHT_SQUARES: u64 = 0x55aa_55aa_55aa_55aa;
+
+const fn is_on_board(file: i32, rank: i32) -> bool {
+    0 <= file && file < 8 && 0 <= rank && rank < 8
+}
+
+const fn leaper_attacks(offsets: &[(i32, i32)]) -> [u64; 64] {
+    let mut attacks = [0; 64];
+    let mut square = 0;
+
+    while square < 64 {
+        let mut i = 0;
+
+        while i < offsets.len() {
+            let file = square as i32 / 8 + offsets[i].0;
+            let rank = square as i32 % 8 + offsets[i].1;
+
+            if is_on_board(file, rank) {
+                attacks[square] |= 1 << (file * 8 + rank);
+            }
+
+            i += 1;
+        }
+
+        square += 1;
+    }
+
+    attacks
+}
+
+const fn rays() -> [[u64; 64]; 8] {
+    let mut rays = [[0; 64]; 8];
+    let mut direction = 0;
+
+    while direction < 8 {
+        let (file_offset, rank_offset) = DIRECTIONS[direction];
+        let mut square = 0;
+
+        while square < 64 {
+            let mut file = square as i32 / 8 + file_offset;
+            let mut rank = square as i32 % 8 + rank_offset;
+
+            while is_on_board(file, rank) {
+                rays[direction][square] |= 1 << (file * 8 + rank);
+                file += file_offset;
+                rank += rank_offset;
+            }
+
+            square += 1;
+        }
+
+        direction += 1;
+    }
+
+    rays
+}
+
+fn ray_attacks(direction: usize, square: usize, occupancy: u64) -> u64 {
+    let (file_offset, rank_offset) = DIRECTIONS[direction];
+    let ray = RAYS[direction][square];
+    let blockers = ray & occupancy;
+
+    if blockers == 0 {
+        return ray;
+    }
+
+    // The nearest blocker has the lowest index on rays running towards higher indices
+    let nearest = if file_offset * 8 + rank_offset > 0 {
+        blockers.trailing_zeros()
+    } else {
+        63 - blockers.leading_zeros()
+    };
+
+    ray ^ RAYS[direction][nearest as usize]
+}
+
+pub fn square_bit(coord: Square) -> u64 {
+    1 << coord as u8
+}
+
+pub fn squares(mut bitboard: u64) -> impl Iterator<Item = Square> {
+    std::iter::from_fn(move || {
+        if bitboard == 0 {
+            return None;
+        }
+
+        let index = bitboard.trailing_zeros();
+        bitboard &= bitboard - 1;
+
+        Some(Square::from_index(index as usize))
+    })
+}
+
+pub fn knight_attacks(coord: Square) -> u64 {
+    KNIGHT_ATTACKS[coord as usize]
+}
+
+pub fn king_attacks(coord: Square) -> u64 {
+    KING_ATTACKS[coord as usize]
+}
+
+pub fn pawn_attacks(coord: Square, color: Color) -> u64 {
+    PAWN_ATTACKS[color as usize][coord as usize]
+}
+
+pub fn bishop_attacks(coord: Square, occupancy: u64) -> u64 {
+    DIAGONALS
+        .iter()
+        .fold(0, |attacks, &direction| attacks | ray_attacks(direction, coord as usize, occupancy))
+}
+
+pub fn rook_attacks(coord: Square, occupancy: u64) -> u64 {
+    LINES
+        .iter()
+        .fold(0, |attacks, &direction| attacks | ray_attacks(direction, coord as usize, occupancy))
+}
+
+pub fn queen_attacks(coord: Square, occupancy: u64) -> u64 {
+    bishop_attacks(coord, occupancy) | rook_attacks(coord, occupancy)
+}
@@ -1,55 +1,137 @@
-use crate::{square::{SquareKind, Square}, pieces::{Piece, Color, MoveStatus}};
+use crate::{square::{SquareKind, Square}, pieces::{Piece, PieceKind, Color, MoveStatus}, castling::{CastlingRights, CastlingSide}, moves::{Move, Undo}, error::MoveError, bitboard, zobrist};
 
+const EMPTY_SQUARE: SquareKind = SquareKind::Empty(MoveStatus::None);
+const EMPTY_FILE: [SquareKind; 8] = [EMPTY_SQUARE; 8];
+
+#[derive(Clone)]
 pub struct Board {
-    square: Vec<Vec<SquareKind>>,
-    capture_board: Vec<(Square, MoveStatus)>,
+    square: [[SquareKind; 8]; 8],
+    // One bitboard per colour and piece kind, plus the squares each colour occupies
+    bitboards: [[u64; 6]; 2],
+    occupancy: [u64; 2],
+    turn: Color,
+    castling_rights: CastlingRights,
+    en_passant: Option<Square>,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+    // Zobrist hash of everything but the en passant square, updated as the board changes
+    hash: u64,
 }
 
 impl Board {
     pub fn new() -> Self {
         Self {
-            square: vec![vec![SquareKind::Empty(MoveStatus::None); 8]; 8],
-            capture_board: Vec::new(),
+            square: [EMPTY_FILE; 8],
+            bitboards: [[0; 6]; 2],
+            occupancy: [0; 2],
+            turn: Color::White,
+            castling_rights: CastlingRights::none(),
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            hash: zobrist::WHITE_TO_MOVE_KEY,
         }
     }
 
-    pub fn spawn(&mut self, mut piece: Piece, coord_to: Square) -> Result<(), &str> {
+    pub fn get_turn(&self) -> Color {
+        self.turn
+    }
+
+    pub fn set_turn(&mut self, turn: Color) {
+        if turn != self.turn {
+            self.hash ^= zobrist::WHITE_TO_MOVE_KEY;
+        }
+
+        self.turn = turn;
+    }
+
+    pub fn get_castling_rights(&self) -> CastlingRights {
+        self.castling_rights
+    }
+
+    pub fn set_castling_rights(&mut self, castling_rights: CastlingRights) {
+        self.hash ^= zobrist::get_castling_key(self.castling_rights) ^ zobrist::get_castling_key(castling_rights);
+        self.castling_rights = castling_rights;
+    }
+
+    pub fn get_en_passant(&self) -> Option<Square> {
+        self.en_passant
+    }
+
+    pub fn set_en_passant(&mut self, en_passant: Option<Square>) {
+        self.en_passant = en_passant;
+    }
+
+    pub fn get_halfmove_clock(&self) -> u32 {
+        self.halfmove_clock
+    }
+
+    pub fn set_halfmove_clock(&mut self, halfmove_clock: u32) {
+        self.halfmove_clock = halfmove_clock;
+    }
+
+    pub fn get_fullmove_number(&self) -> u32 {
+        self.fullmove_number
+    }
+
+    pub fn set_fullmove_number(&mut self, fullmove_number: u32) {
+        self.fullmove_number = fullmove_number;
+    }
+
+    // Polyglot-compatible Zobrist hash of the position, leaving out the move counters
+    pub fn get_hash(&self) -> u64 {
+        self.hash ^ zobrist::get_en_passant_key(self)
+    }
+
+    pub fn get_pieces(&self, color: Color, kind: PieceKind) -> u64 {
+        self.bitboards[color as usize][kind as usize]
+    }
+
+    pub fn get_occupancy(&self, color: Color) -> u64 {
+        self.occupancy[color as usize]
+    }
+
+    pub fn get_all_occupancy(&self) -> u64 {
+        self.occupancy[0] | self.occupancy[1]
+    }
+
+    fn toggle_bitboards(&mut self, piece: &Piece, coord: Square) {
+        let bit = bitboard::square_bit(coord);
+        let color = piece.get_color() as usize;
+
+        self.bitboards[color][piece.get_kind() as usize] ^= bit;
+        self.occupancy[color] ^= bit;
+        self.hash ^= zobrist::get_piece_key(piece.get_color(), piece.get_kind(), coord);
+    }
+
+    pub fn spawn(&mut self, mut piece: Piece, coord_to: Square) -> Result<(), MoveError> {
         let file = coord_to.get_file() as usize;
         let rank = coord_to.get_rank() as usize;
 
         match &self.square[file][rank].clone() {
             SquareKind::Empty(status) => {
                 piece.set_coord(coord_to);
-                piece.get_valid_moves(self, coord_to)
-                    .iter()
-                    .for_each(|i| {
-                        match i.1 {
-                            MoveStatus::Capturable {..} => self.capture_board.push((i.0, i.1)),
-                            MoveStatus::Threaten {..} => self.capture_board.push((i.0, i.1)),
-                            MoveStatus::Pierced  {..} => self.capture_board.push((i.0, i.1)),
-                            _ => (),
-                        }
-                    });
 
+                self.toggle_bitboards(&piece, coord_to);
                 self.square[file][rank] = SquareKind::Occupied(piece, *status);
 
                 Ok(())
             },
             SquareKind::Occupied(_, _) => {
-                Err("Square is not empty")
+                Err(MoveError::SquareOccupied(coord_to))
             },
         }
     }
 
-    pub fn despawn(&mut self, square: Square) -> Result<(), &str> {
+    pub fn despawn(&mut self, square: Square) -> Result<(), MoveError> {
         let file = square.get_file() as usize;
         let rank = square.get_rank() as usize;
 
-        match self.square[file][rank] {
-            SquareKind::Empty(_) => Err("Square is empty"),
-            SquareKind::Occupied(_, _) => {
+        match self.square[file][rank].clone() {
+            SquareKind::Empty(_) => Err(MoveError::NoPieceOnSquare(square)),
+            SquareKind::Occupied(piece, _) => {
+                self.toggle_bitboards(&piece, square);
                 self.square[file][rank] = SquareKind::Empty(MoveStatus::None);
-                self.update_capture_board();
                 Ok(())
             },
         }
@@ -65,14 +147,30 @@ impl Board {
         }
     }
 
-    pub fn is_under_attack(&self, square: Square, by_color: Color) -> bool {
-        let file = square.get_file() as usize;
-        let rank = square.get_rank() as usize;
+    // Neither side can ever mate: bare kings, one minor piece, or bishops all on squares of one colour
+    pub fn is_insufficient_material(&self) -> bool {
+        let get_both = |kind: PieceKind| self.get_pieces(Color::White, kind) | self.get_pieces(Color::Black, kind);
 
-        match &self.square[file][rank] {
-            SquareKind::Empty(_) => false,
-            SquareKind::Occupied(piece, _) => piece.get_color() != by_color,
+        if get_both(PieceKind::Pawn) | get_both(PieceKind::Rook) | get_both(PieceKind::Queen) != 0 {
+            return false;
         }
+
+        let bishops = get_both(PieceKind::Bishop);
+
+        match (get_both(PieceKind::Knight).count_ones(), bishops.count_ones()) {
+            (0, 0..=1) | (1, 0) => true,
+            (0, _) => bishops & bitboard::LIGHT_SQUARES == 0 || bishops & !bitboard::LIGHT_SQUARES == 0,
+            _ => false,
+        }
+    }
+
+    pub fn find_king(&self, color: Color) -> Option<Square> {
+        bitboard::squares(self.get_pieces(color, PieceKind::King)).next()
+    }
+
+    pub fn is_in_check(&self, color: Color) -> bool {
+        self.find_king(color)
+            .is_some_and(|king| self.is_attacked(king, color.opposite()))
     }
 
     pub fn get_piece(&self, square: Square) -> Option<&Piece> {
@@ -85,72 +183,284 @@ impl Board {
         }
     }
 
-    pub fn get_piece_mut(&mut self, square: Square) -> Option<&mut Piece> {
-        let file = square.get_file() as usize;
-        let rank = square.get_rank() as usize;
+    pub(crate) fn get_legal_moves(&self, coord: Square) -> Vec<(Square, MoveStatus)> {
+        let Some(color) = self.get_piece(coord).map(|x| x.get_color()) else {
+            return Vec::new();
+        };
 
-        match &mut self.square[file][rank] {
-            SquareKind::Empty(_) => None,
-            SquareKind::Occupied(piece, _) => Some(piece),
+        self.get_candidate_moves(coord)
+            .into_iter()
+            .filter(|i| self.is_king_safe_after(coord, i.0, i.1, color))
+            .collect()
+    }
+
+    // The moves a piece could make if its own king's safety did not matter
+    fn get_candidate_moves(&self, coord: Square) -> Vec<(Square, MoveStatus)> {
+        let Some(piece) = self.get_piece(coord).cloned() else {
+            return Vec::new();
+        };
+        let color = piece.get_color();
+
+        piece.get_valid_moves(self, coord)
+            .into_iter()
+            .filter(|i| {
+                matches!(
+                    i.1,
+                    MoveStatus::Capturable {..} |
+                    MoveStatus::EnPassant {..} |
+                    MoveStatus::Movable {..} |
+                    MoveStatus::Castling {..}
+                )
+            })
+            .filter(|i| self.get_piece(i.0).is_none_or(|x| x.get_color() != color))
+            .collect()
+    }
+
+    // Checks a move for the side to move, telling why it cannot be played
+    pub fn validate_move(&self, mv: Move) -> Result<(), MoveError> {
+        let coord_from = mv.get_from();
+        let coord_to = mv.get_to();
+        let color = self.get_piece(coord_from)
+            .ok_or(MoveError::NoPieceOnSquare(coord_from))?
+            .get_color();
+
+        if color != self.turn {
+            return Err(MoveError::NotYourTurn);
+        }
+
+        let Some((_, move_kind)) = self.get_candidate_moves(coord_from).into_iter().find(|i| i.0 == coord_to) else {
+            return Err(MoveError::IllegalMove);
+        };
+
+        if !self.is_king_safe_after(coord_from, coord_to, move_kind, color) {
+            return Err(MoveError::KingInCheck);
+        }
+
+        match (self.is_promotion(coord_from, coord_to), mv.get_promotion()) {
+            (true, None) => Err(MoveError::PromotionRequired),
+            (true, Some(kind)) if !PieceKind::PROMOTIONS.contains(&kind) => Err(MoveError::InvalidPromotion),
+            (false, Some(_)) => Err(MoveError::InvalidPromotion),
+            _ => Ok(()),
         }
     }
 
-    pub fn get_square_mut(&mut self, square: Square) -> Option<&mut SquareKind> {
-        let file = square.get_file() as usize;
-        let rank = square.get_rank() as usize;
+    pub(crate) fn get_all_legal_moves(&self, color: Color) -> Vec<(Square, Square, MoveStatus)> {
+        let mut legal_moves = Vec::new();
+
+        for coord in bitboard::squares(self.get_occupancy(color)) {
+            self.get_legal_moves(coord)
+                .into_iter()
+                .for_each(|i| legal_moves.push((coord, i.0, i.1)));
+        }
+
+        legal_moves
+    }
 
-        Some(&mut self.square[file][rank])
+    // Every legal move for the side to move, with one entry per promotion piece
+    pub fn get_all_playable_moves(&self) -> Vec<Move> {
+        let mut playable_moves = Vec::new();
+
+        for (coord_from, coord_to, _) in self.get_all_legal_moves(self.turn) {
+            if self.is_promotion(coord_from, coord_to) {
+                playable_moves.extend(PieceKind::PROMOTIONS.iter().map(|&kind| Move::new(coord_from, coord_to, Some(kind))));
+            } else {
+                playable_moves.push(Move::new(coord_from, coord_to, None));
+            }
+        }
+
+        playable_moves
+    }
+
+    fn is_king_safe_after(&self, coord_from: Square, coord_to: Square, move_kind: MoveStatus, color: Color) -> bool {
+        if let MoveStatus::Castling {..} = move_kind {
+            // The king can neither castle out of check nor pass through an attacked square
+            if self.is_in_check(color) {
+                return false;
+            }
+
+            let rank = coord_from.get_rank();
+            let step = if coord_to.get_file() > coord_from.get_file() { 1 } else { -1 };
+            let mut file = coord_from.get_file() + step;
+
+            while file != coord_to.get_file() {
+                if self.is_attacked(Square::from_position((file, rank)), color.opposite()) {
+                    return false;
+                }
+
+                file += step;
+            }
+        }
+
+        // Looks at the board the move leaves behind without playing it; a castling rook never uncovers its king
+        let bit_from = bitboard::square_bit(coord_from);
+        let bit_to = bitboard::square_bit(coord_to);
+        let bit_captured = match move_kind {
+            MoveStatus::EnPassant {..} => bitboard::square_bit(Square::from_position((coord_to.get_file(), coord_from.get_rank()))),
+            _ => bit_to,
+        };
+        let occupancy = self.get_all_occupancy() & !bit_from & !bit_captured | bit_to;
+
+        let king = match self.get_pieces(color, PieceKind::King) & bit_from {
+            0 => self.find_king(color),
+            _ => Some(coord_to),
+        };
+
+        king.is_none_or(|king| self.get_attackers(king, color.opposite(), occupancy) & !bit_captured == 0)
     }
 
-    pub fn get_capture_board(&mut self) -> &Vec<(Square, MoveStatus)> {
-        &self.capture_board
+    // Plays a move, promoting right away when it names a promotion piece
+    pub fn make_move(&mut self, mv: Move) -> Result<Undo, MoveError> {
+        let coord_from = mv.get_from();
+        let coord_to = mv.get_to();
+
+        let mut piece = self.get_piece(coord_from)
+            .ok_or(MoveError::NoPieceOnSquare(coord_from))?
+            .clone();
+        let is_pawn = matches!(piece, Piece::P(_));
+
+        // Checked before anything moves, so a refused move leaves the board untouched.
+        // A pawn may still reach the last rank without a piece, for `promote` to choose it afterwards
+        match (self.is_promotion(coord_from, coord_to), mv.get_promotion()) {
+            (false, Some(_)) => return Err(MoveError::InvalidPromotion),
+            (true, Some(kind)) if !PieceKind::PROMOTIONS.contains(&kind) => return Err(MoveError::InvalidPromotion),
+            _ => (),
+        }
+
+        // Taking en passant removes the pawn beside the target square rather than on it
+        let captured_coord = if is_pawn && self.en_passant == Some(coord_to) && coord_to.get_file() != coord_from.get_file() {
+            Square::from_position((coord_to.get_file(), coord_from.get_rank()))
+        } else {
+            coord_to
+        };
+
+        let undo = Undo {
+            mv,
+            piece: piece.clone(),
+            captured: self.get_piece(captured_coord).cloned().map(|x| (x, captured_coord)),
+            castling_rights: self.castling_rights,
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            hash: self.hash,
+        };
+
+        piece.move_to(self, coord_to)?;
+
+        if let Some(kind) = mv.get_promotion() {
+            self.promote(coord_to, kind)?;
+        }
+
+        if let Piece::K(king) = piece {
+            self.castling_rights.revoke_all(king.get_color());
+        }
+
+        self.castling_rights.revoke_corner(coord_from);
+        self.castling_rights.revoke_corner(coord_to);
+        self.hash ^= zobrist::get_castling_key(undo.castling_rights) ^ zobrist::get_castling_key(self.castling_rights);
+
+        // The square skipped by a double pawn push stays capturable en passant for one move
+        self.en_passant = if is_pawn && (coord_to.get_rank() - coord_from.get_rank()).abs() == 2 {
+            Some(Square::from_position((coord_from.get_file(), (coord_from.get_rank() + coord_to.get_rank()) / 2)))
+        } else {
+            None
+        };
+
+        if is_pawn || undo.captured.is_some() {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock = self.halfmove_clock.saturating_add(1);
+        }
+
+        if self.turn == Color::Black {
+            self.fullmove_number = self.fullmove_number.saturating_add(1);
+        }
+
+        self.turn = self.turn.opposite();
+        self.hash ^= zobrist::WHITE_TO_MOVE_KEY;
+
+        Ok(undo)
     }
 
-    pub fn update_capture_board(&mut self) {
-        self.capture_board.clear();
+    pub fn unmake_move(&mut self, undo: &Undo) -> Result<(), MoveError> {
+        let coord_from = undo.mv.get_from();
+        let coord_to = undo.mv.get_to();
+        let color = undo.piece.get_color();
+
+        self.despawn(coord_to)?;
+        self.spawn(undo.piece.clone(), coord_from)?;
 
-        for (i, rank) in self.square.clone().iter().enumerate() {
-            for (j, square) in rank.iter().enumerate() {
-                if let SquareKind::Occupied(piece, _) = square {
-                    piece
-                        .get_valid_moves(self, Square::from_position((i as i32, j as i32)))
-                        .iter()
-                        .for_each(|i| {
-                            match i.1 {
-                                MoveStatus::Capturable {..} => self.capture_board.push((i.0, i.1)),
-                                MoveStatus::Threaten {..} => self.capture_board.push((i.0, i.1)),
-                                MoveStatus::Pierced {..} => self.capture_board.push((i.0, i.1)),
-                                _ => (),
-                            }
-                        });
-                    }
+        // A castling king brings its rook back along
+        if let Piece::K(_) = undo.piece {
+            for side in [CastlingSide::KingSide, CastlingSide::QueenSide] {
+                if coord_from.get_file() == 4 && coord_to == side.get_king_target(color) {
+                    self.move_piece(side.get_rook_target(color), side.get_rook_coord(color))?;
+                }
             }
         }
+
+        if let Some((piece, coord)) = &undo.captured {
+            self.spawn(piece.clone(), *coord)?;
+        }
+
+        self.castling_rights = undo.castling_rights;
+        self.en_passant = undo.en_passant;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.fullmove_number = undo.fullmove_number;
+        self.turn = color;
+        self.hash = undo.hash;
+
+        Ok(())
+    }
+
+    pub fn is_promotion(&self, coord_from: Square, coord_to: Square) -> bool {
+        matches!(self.get_piece(coord_from), Some(Piece::P(_))) &&
+            (coord_to.get_rank() == 0 || coord_to.get_rank() == 7)
+    }
+
+    pub fn promote(&mut self, coord: Square, kind: PieceKind) -> Result<(), MoveError> {
+        let Some(Piece::P(pawn)) = self.get_piece(coord) else {
+            return Err(MoveError::NoPromotionPending);
+        };
+
+        let piece = match kind {
+            PieceKind::Queen => pawn.try_into_queen().map(Piece::Q),
+            PieceKind::Rook => pawn.try_into_rook().map(Piece::R),
+            PieceKind::Bishop => pawn.try_into_bishop().map(Piece::B),
+            PieceKind::Knight => pawn.try_into_knight().map(Piece::N),
+            PieceKind::Pawn | PieceKind::King => None,
+        }.ok_or(MoveError::InvalidPromotion)?;
+
+        self.despawn(coord)?;
+        self.spawn(piece, coord)
     }
 
-    pub fn move_piece(&mut self, coord_from: Square, coord_to: Square) -> Result<(), &'static str> {
+    pub(crate) fn move_piece(&mut self, coord_from: Square, coord_to: Square) -> Result<(), MoveError> {
         let file_from = coord_from.get_file() as usize;
         let rank_from = coord_from.get_rank() as usize;
 
         let file_to = coord_to.get_file() as usize;
         let rank_to = coord_to.get_rank() as usize;
 
-        match self.square[file_from][rank_from] {
-            SquareKind::Empty(_) => Err("Square is empty"),
-            SquareKind::Occupied(ref mut piece, status) => {
+        match self.square[file_from][rank_from].clone() {
+            SquareKind::Empty(_) => Err(MoveError::NoPieceOnSquare(coord_from)),
+            SquareKind::Occupied(mut piece, status) => {
+                if let SquareKind::Occupied(captured, _) = self.square[file_to][rank_to].clone() {
+                    self.toggle_bitboards(&captured, coord_to);
+                }
+
+                self.toggle_bitboards(&piece, coord_from);
+                self.toggle_bitboards(&piece, coord_to);
                 piece.set_coord(coord_to);
 
-                self.square[file_to][rank_to] = SquareKind::Occupied(piece.clone(), status);
+                self.square[file_to][rank_to] = SquareKind::Occupied(piece, status);
                 self.square[file_from][rank_from] = SquareKind::Empty(status);
                 
-                self.update_capture_board();
-                
                 Ok(())  
             },
         }
     }
 
-    pub fn mark_valid_moves(&mut self, move_kind: MoveStatus, coord: Square) {
+    pub(crate) fn mark_valid_moves(&mut self, move_kind: MoveStatus, coord: Square) {
         let file = coord.get_file() as usize;
         let rank = coord.get_rank() as usize;
         
@@ -160,7 +470,7 @@ impl Board {
         };
     }
 
-    pub fn clear_marks(&mut self) {
+    pub(crate) fn clear_marks(&mut self) {
         for rank in self.square.iter_mut() {
             for square in rank.iter_mut() {
                 *square = match square {
@@ -184,13 +494,44 @@ impl Board {
     }
 }
 
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Shows the board from Black's side
+pub struct Flipped<'a>(pub &'a Board);
+
+impl std::fmt::Display for Flipped<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.write_board(f, true)
+    }
+}
+
 impl std::fmt::Display for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "   A B C D E F G H")?;
+        self.write_board(f, false)
+    }
+}
+
+impl Board {
+    fn write_board(&self, f: &mut std::fmt::Formatter<'_>, flipped: bool) -> std::fmt::Result {
+        let (files, ranks) = match flipped {
+            false => ([0, 1, 2, 3, 4, 5, 6, 7], [7, 6, 5, 4, 3, 2, 1, 0]),
+            true => ([7, 6, 5, 4, 3, 2, 1, 0], [0, 1, 2, 3, 4, 5, 6, 7]),
+        };
+        let header = files
+            .iter()
+            .map(|&file| ((b'A' + file as u8) as char).to_string())
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        writeln!(f, "   {}", header)?;
         writeln!(f, "  ╔════════════════╗")?;
-        for rank in (0..8).rev() {
+        for rank in ranks {
             write!(f, "{} ║", rank + 1)?;
-            for file in 0..8 {
+            for file in files {
                 if (file + rank) % 2 == 1 {
                     write!(f, "\x1b[48;5;235m")?;
                 } else {
@@ -268,7 +609,7 @@ impl std::fmt::Display for Board {
             writeln!(f, "║ {}", rank + 1)?;
         }
         writeln!(f, "  ╚════════════════╝")?;
-        writeln!(f, "   A B C D E F G H")?;
+        writeln!(f, "   {}", header)?;
 
         Ok(())
     }
@@ -0,0 +1,154 @@
+use std::str::FromStr;
+
+use crate::{pieces::Color, square::Square, error::FenError};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CastlingSide {
+    KingSide,
+    QueenSide,
+}
+
+impl CastlingSide {
+    pub fn get_rook_coord(self, color: Color) -> Square {
+        match (color, self) {
+            (Color::White, CastlingSide::KingSide) => Square::H1,
+            (Color::White, CastlingSide::QueenSide) => Square::A1,
+            (Color::Black, CastlingSide::KingSide) => Square::H8,
+            (Color::Black, CastlingSide::QueenSide) => Square::A8,
+        }
+    }
+
+    pub fn get_king_target(self, color: Color) -> Square {
+        match (color, self) {
+            (Color::White, CastlingSide::KingSide) => Square::G1,
+            (Color::White, CastlingSide::QueenSide) => Square::C1,
+            (Color::Black, CastlingSide::KingSide) => Square::G8,
+            (Color::Black, CastlingSide::QueenSide) => Square::C8,
+        }
+    }
+
+    pub fn get_rook_target(self, color: Color) -> Square {
+        match (color, self) {
+            (Color::White, CastlingSide::KingSide) => Square::F1,
+            (Color::White, CastlingSide::QueenSide) => Square::D1,
+            (Color::Black, CastlingSide::KingSide) => Square::F8,
+            (Color::Black, CastlingSide::QueenSide) => Square::D8,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CastlingRights {
+    white_king_side: bool,
+    white_queen_side: bool,
+    black_king_side: bool,
+    black_queen_side: bool,
+}
+
+impl CastlingRights {
+    pub fn all() -> Self {
+        Self {
+            white_king_side: true,
+            white_queen_side: true,
+            black_king_side: true,
+            black_queen_side: true,
+        }
+    }
+
+    pub fn none() -> Self {
+        Self {
+            white_king_side: false,
+            white_queen_side: false,
+            black_king_side: false,
+            black_queen_side: false,
+        }
+    }
+
+    pub fn can_castle(&self, color: Color, side: CastlingSide) -> bool {
+        match (color, side) {
+            (Color::White, CastlingSide::KingSide) => self.white_king_side,
+            (Color::White, CastlingSide::QueenSide) => self.white_queen_side,
+            (Color::Black, CastlingSide::KingSide) => self.black_king_side,
+            (Color::Black, CastlingSide::QueenSide) => self.black_queen_side,
+        }
+    }
+
+    pub fn revoke(&mut self, color: Color, side: CastlingSide) {
+        match (color, side) {
+            (Color::White, CastlingSide::KingSide) => self.white_king_side = false,
+            (Color::White, CastlingSide::QueenSide) => self.white_queen_side = false,
+            (Color::Black, CastlingSide::KingSide) => self.black_king_side = false,
+            (Color::Black, CastlingSide::QueenSide) => self.black_queen_side = false,
+        }
+    }
+
+    pub fn revoke_all(&mut self, color: Color) {
+        self.revoke(color, CastlingSide::KingSide);
+        self.revoke(color, CastlingSide::QueenSide);
+    }
+
+    // Any move from or onto a rook's home corner means that rook has moved or been captured
+    pub fn revoke_corner(&mut self, coord: Square) {
+        match coord {
+            Square::H1 => self.revoke(Color::White, CastlingSide::KingSide),
+            Square::A1 => self.revoke(Color::White, CastlingSide::QueenSide),
+            Square::H8 => self.revoke(Color::Black, CastlingSide::KingSide),
+            Square::A8 => self.revoke(Color::Black, CastlingSide::QueenSide),
+            _ => (),
+        }
+    }
+}
+
+impl FromStr for CastlingRights {
+    type Err = FenError;
+
+    fn from_str(s: &str) -> Result<CastlingRights, FenError> {
+        let mut rights = CastlingRights::none();
+
+        if s == "-" {
+            return Ok(rights);
+        }
+
+        if s.is_empty() {
+            return Err(FenError::InvalidCastlingRights);
+        }
+
+        for c in s.chars() {
+            match c {
+                'K' => rights.white_king_side = true,
+                'Q' => rights.white_queen_side = true,
+                'k' => rights.black_king_side = true,
+                'q' => rights.black_queen_side = true,
+                _ => return Err(FenError::InvalidCastlingRights),
+            }
+        }
+
+        Ok(rights)
+    }
+}
+
+impl std::fmt::Display for CastlingRights {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if *self == CastlingRights::none() {
+            return write!(f, "-");
+        }
+
+        if self.white_king_side {
+            write!(f, "K")?;
+        }
+
+        if self.white_queen_side {
+            write!(f, "Q")?;
+        }
+
+        if self.black_king_side {
+            write!(f, "k")?;
+        }
+
+        if self.black_queen_side {
+            write!(f, "q")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use crate::square::Square;
+
+// Why a move, or a step of playing one, was refused
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MoveError {
+    NoPieceOnSquare(Square),
+    SquareOccupied(Square),
+    // A piece has to be selected before it can be moved
+    NoPieceSelected,
+    NotYourTurn,
+    // The piece cannot reach the target square at all
+    IllegalMove,
+    // The piece can reach the target square, but its own king would be left in check
+    KingInCheck,
+    // A pawn reaching the last rank needs its promotion piece first
+    PromotionRequired,
+    NoPromotionPending,
+    InvalidPromotion,
+    GameOver,
+    NothingToUndo,
+    NothingToRedo,
+    NoMovePlayed,
+    NoDrawToClaim,
+    InvalidNotation,
+    AmbiguousMove,
+}
+
+impl std::fmt::Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::NoPieceOnSquare(square) => write!(f, "No piece on {}", square),
+            MoveError::SquareOccupied(square) => write!(f, "{} is already occupied", square),
+            MoveError::NoPieceSelected => write!(f, "No piece is selected"),
+            MoveError::NotYourTurn => write!(f, "Not your turn"),
+            MoveError::IllegalMove => write!(f, "Illegal move"),
+            MoveError::KingInCheck => write!(f, "That move leaves the king in check"),
+            MoveError::PromotionRequired => write!(f, "The pawn has to be promoted"),
+            MoveError::NoPromotionPending => write!(f, "No pawn to promote"),
+            MoveError::InvalidPromotion => write!(f, "Invalid promotion piece"),
+            MoveError::GameOver => write!(f, "The game is over"),
+            MoveError::NothingToUndo => write!(f, "Nothing to undo"),
+            MoveError::NothingToRedo => write!(f, "Nothing to redo"),
+            MoveError::NoMovePlayed => write!(f, "No move has been played"),
+            MoveError::NoDrawToClaim => write!(f, "There is no draw to claim"),
+            MoveError::InvalidNotation => write!(f, "Invalid move notation"),
+            MoveError::AmbiguousMove => write!(f, "Ambiguous move"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+// Why a FEN record could not be read
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FenError {
+    MissingPiecePlacement,
+    MissingSideToMove,
+    MissingCastlingRights,
+    MissingEnPassant,
+    TooManyFields,
+    InvalidRankCount,
+    InvalidRankLength,
+    InvalidEmptySquareCount,
+    InvalidPiece(char),
+    PawnOnBackRank,
+    // Each side needs exactly one king
+    InvalidKingCount,
+    // The side to move could capture the enemy king
+    OpponentInCheck,
+    InvalidSideToMove,
+    InvalidCastlingRights,
+    InvalidEnPassant,
+    InvalidHalfmoveClock,
+    InvalidFullmoveNumber,
+}
+
+impl std::fmt::Display for FenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FenError::MissingPiecePlacement => write!(f, "Missing piece placement"),
+            FenError::MissingSideToMove => write!(f, "Missing side to move"),
+            FenError::MissingCastlingRights => write!(f, "Missing castling availability"),
+            FenError::MissingEnPassant => write!(f, "Missing en passant square"),
+            FenError::TooManyFields => write!(f, "Too many fields"),
+            FenError::InvalidRankCount => write!(f, "Piece placement must have 8 ranks"),
+            FenError::InvalidRankLength => write!(f, "Every rank must have 8 squares"),
+            FenError::InvalidEmptySquareCount => write!(f, "Invalid empty square count"),
+            FenError::InvalidPiece(c) => write!(f, "Invalid piece letter '{}'", c),
+            FenError::PawnOnBackRank => write!(f, "Pawns cannot stand on the first or last rank"),
+            FenError::InvalidKingCount => write!(f, "Each side must have exactly one king"),
+            FenError::OpponentInCheck => write!(f, "The side not to move is in check"),
+            FenError::InvalidSideToMove => write!(f, "Invalid side to move"),
+            FenError::InvalidCastlingRights => write!(f, "Invalid castling availability"),
+            FenError::InvalidEnPassant => write!(f, "Invalid en passant square"),
+            FenError::InvalidHalfmoveClock => write!(f, "Invalid halfmove clock"),
+            FenError::InvalidFullmoveNumber => write!(f, "Invalid fullmove number"),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
@@ -0,0 +1,482 @@
+use crate::{board::Board, square::Square, pieces::{Color, PieceKind}, bitboard};
+
+const KINDS: [PieceKind; 6] = [PieceKind::Pawn, PieceKind::Knight, PieceKind::Bishop, PieceKind::Rook, PieceKind::Queen, PieceKind::King];
+
+// Knights and bishops count 1, rooks 2 and queens 4 towards the 24 of the opening
+pub const MAX_PHASE: i32 = 24;
+
+const FILE_A: u64 = 0xFF;
+const RANK_1: u64 = 0x0101010101010101;
+
+const PASSED_PAWN_BONUS: [Score; 8] = [
+    Score::new(0, 0), Score::new(5, 10), Score::new(10, 20), Score::new(20, 40),
+    Score::new(35, 70), Score::new(60, 120), Score::new(100, 200), Score::new(0, 0),
+];
+const DOUBLED_PAWN_PENALTY: Score = Score::new(-10, -20);
+const ISOLATED_PAWN_PENALTY: Score = Score::new(-10, -15);
+const PAWN_SHIELD_BONUS: Score = Score::new(10, 0);
+
+// The tables read like a diagram from White's side: the first row is the eighth rank
+#[rustfmt::skip]
+const PAWN_MIDDLEGAME: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     50,  50,  50,  50,  50,  50,  50,  50,
+     10,  10,  20,  30,  30,  20,  10,  10,
+      5,   5,  10,  25,  25,  10,   5,   5,
+      0,   0,   0,  20,  20,   0,   0,   0,
+      5,  -5, -10,   0,   0, -10,  -5,   5,
+      5,  10,  10, -20, -20,  10,  10,   5,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const PAWN_ENDGAME: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     80,  80,  80,  80,  80,  80,  80,  80,
+     50,  50,  50,  50,  50,  50,  50,  50,
+     30,  30,  30,  30,  30,  30,  30,  30,
+     20,  20,  20,  20,  20,  20,  20,  20,
+     10,  10,  10,  10,  10,  10,  10,  10,
+     10,  10,  10,  10,  10,  10,  10,  10,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_TABLE: [i32; 64] = [
+    -50, -40, -30, -30, -30, -30, -40, -50,
+    -40, -20,   0,   0,   0,   0, -20, -40,
+    -30,   0,  10,  15,  15,  10,   0, -30,
+    -30,   5,  15,  20,  20,  15,   5, -30,
+    -30,   0,  15,  20,  20,  15,   0, -30,
+    -30,   5,  10,  15,  15,  10,   5, -30,
+    -40, -20,   0,   5,   5,   0, -20, -40,
+    -50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+#[rustfmt::skip]
+const BISHOP_TABLE: [i32; 64] = [
+    -20, -10, -10, -10, -10, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,  10,  10,   5,   0, -10,
+    -10,   5,   5,  10,  10,   5,   5, -10,
+    -10,   0,  10,  10,  10,  10,   0, -10,
+    -10,  10,  10,  10,  10,  10,  10, -10,
+    -10,   5,   0,   0,   0,   0,   5, -10,
+    -20, -10, -10, -10, -10, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const ROOK_TABLE: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+      5,  10,  10,  10,  10,  10,  10,   5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+      0,   0,   0,   5,   5,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const QUEEN_TABLE: [i32; 64] = [
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,   5,   5,   5,   0, -10,
+     -5,   0,   5,   5,   5,   5,   0,  -5,
+      0,   0,   5,   5,   5,   5,   0,  -5,
+    -10,   5,   5,   5,   5,   5,   0, -10,
+    -10,   0,   5,   0,   0,   0,   0, -10,
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const KING_MIDDLEGAME: [i32; 64] = [
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -20, -30, -30, -40, -40, -30, -30, -20,
+    -10, -20, -20, -20, -20, -20, -20, -10,
+     20,  20,   0,   0,   0,   0,  20,  20,
+     20,  30,  10,   0,   0,  10,  30,  20,
+];
+
+#[rustfmt::skip]
+const KING_ENDGAME: [i32; 64] = [
+    -50, -40, -30, -20, -20, -30, -40, -50,
+    -30, -20, -10,   0,   0, -10, -20, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -30,   0,   0,   0,   0, -30, -30,
+    -50, -30, -30, -30, -30, -30, -30, -50,
+];
+
+// A middlegame and an endgame value, blended by the phase of the game
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+struct Score {
+    middlegame: i32,
+    endgame: i32,
+}
+
+impl Score {
+    const fn new(middlegame: i32, endgame: i32) -> Self {
+        Self { middlegame, endgame }
+    }
+
+    fn add(&mut self, other: Score, times: i32) {
+        self.middlegame += other.middlegame * times;
+        self.endgame += other.endgame * times;
+    }
+
+    fn taper(self, phase: i32) -> i32 {
+        (self.middlegame * phase + self.endgame * (MAX_PHASE - phase)) / MAX_PHASE
+    }
+}
+
+// One term of the evaluation, in centipawns for each side
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Term {
+    pub white: i32,
+    pub black: i32,
+}
+
+impl Term {
+    fn new(white: Score, black: Score, phase: i32) -> Self {
+        Self {
+            white: white.taper(phase),
+            black: black.taper(phase),
+        }
+    }
+
+    // Positive when the term favours White
+    pub fn get_balance(&self) -> i32 {
+        self.white - self.black
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Evaluation {
+    pub material: Term,
+    pub piece_squares: Term,
+    pub mobility: Term,
+    pub king_safety: Term,
+    pub pawn_structure: Term,
+    // From MAX_PHASE with every piece on the board down to 0 with only kings and pawns
+    pub phase: i32,
+}
+
+impl Evaluation {
+    pub fn get_terms(&self) -> [(&'static str, Term); 5] {
+        [
+            ("Material", self.material),
+            ("Piece squares", self.piece_squares),
+            ("Mobility", self.mobility),
+            ("King safety", self.king_safety),
+            ("Pawn structure", self.pawn_structure),
+        ]
+    }
+
+    // Positive when the position favours White
+    pub fn get_total(&self) -> i32 {
+        self.get_terms().iter().map(|(_, term)| term.get_balance()).sum()
+    }
+
+    pub fn get_score(&self, color: Color) -> i32 {
+        match color {
+            Color::White => self.get_total(),
+            Color::Black => -self.get_total(),
+        }
+    }
+}
+
+impl std::fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:<16}{:>8}{:>8}{:>8}", "Term", "White", "Black", "Total")?;
+
+        for (name, term) in self.get_terms() {
+            writeln!(f, "{:<16}{:>8}{:>8}{:>8}", name, term.white, term.black, term.get_balance())?;
+        }
+
+        writeln!(f, "{:<16}{:>24}", "Phase", format!("{}/{}", self.phase, MAX_PHASE))?;
+        writeln!(f, "{:<16}{:>24}", "Total", self.get_total())
+    }
+}
+
+pub fn get_piece_value(kind: PieceKind) -> i32 {
+    get_material(kind).middlegame
+}
+
+fn get_material(kind: PieceKind) -> Score {
+    match kind {
+        PieceKind::Pawn => Score::new(82, 94),
+        PieceKind::Knight => Score::new(337, 281),
+        PieceKind::Bishop => Score::new(365, 297),
+        PieceKind::Rook => Score::new(477, 512),
+        PieceKind::Queen => Score::new(1025, 936),
+        PieceKind::King => Score::new(0, 0),
+    }
+}
+
+fn get_phase_weight(kind: PieceKind) -> i32 {
+    match kind {
+        PieceKind::Knight | PieceKind::Bishop => 1,
+        PieceKind::Rook => 2,
+        PieceKind::Queen => 4,
+        PieceKind::Pawn | PieceKind::King => 0,
+    }
+}
+
+fn get_mobility_weight(kind: PieceKind) -> Score {
+    match kind {
+        PieceKind::Knight => Score::new(4, 4),
+        PieceKind::Bishop => Score::new(5, 5),
+        PieceKind::Rook => Score::new(2, 4),
+        PieceKind::Queen => Score::new(1, 2),
+        PieceKind::Pawn | PieceKind::King => Score::new(0, 0),
+    }
+}
+
+// Weight of an enemy piece hitting the squares around the king
+fn get_king_attack_weight(kind: PieceKind) -> Score {
+    match kind {
+        PieceKind::Knight | PieceKind::Bishop => Score::new(-20, -5),
+        PieceKind::Rook => Score::new(-40, -10),
+        PieceKind::Queen => Score::new(-80, -20),
+        PieceKind::Pawn | PieceKind::King => Score::new(0, 0),
+    }
+}
+
+fn get_piece_square(kind: PieceKind, color: Color, square: Square) -> Score {
+    // Black reads the same tables upside down
+    let row = match color {
+        Color::White => 7 - square.get_rank(),
+        Color::Black => square.get_rank(),
+    };
+    let index = (row * 8 + square.get_file()) as usize;
+
+    let (middlegame, endgame) = match kind {
+        PieceKind::Pawn => (&PAWN_MIDDLEGAME, &PAWN_ENDGAME),
+        PieceKind::Knight => (&KNIGHT_TABLE, &KNIGHT_TABLE),
+        PieceKind::Bishop => (&BISHOP_TABLE, &BISHOP_TABLE),
+        PieceKind::Rook => (&ROOK_TABLE, &ROOK_TABLE),
+        PieceKind::Queen => (&QUEEN_TABLE, &QUEEN_TABLE),
+        PieceKind::King => (&KING_MIDDLEGAME, &KING_ENDGAME),
+    };
+
+    Score::new(middlegame[index], endgame[index])
+}
+
+fn get_attacks(board: &Board, kind: PieceKind, color: Color, square: Square) -> u64 {
+    let occupancy = board.get_all_occupancy();
+
+    match kind {
+        PieceKind::Pawn => bitboard::pawn_attacks(square, color),
+        PieceKind::Knight => bitboard::knight_attacks(square),
+        PieceKind::Bishop => bitboard::bishop_attacks(square, occupancy),
+        PieceKind::Rook => bitboard::rook_attacks(square, occupancy),
+        PieceKind::Queen => bitboard::queen_attacks(square, occupancy),
+        PieceKind::King => bitboard::king_attacks(square),
+    }
+}
+
+fn get_file_mask(file: i32) -> u64 {
+    FILE_A << (file * 8)
+}
+
+fn get_adjacent_files_mask(file: i32) -> u64 {
+    (file - 1..=file + 1)
+        .filter(|x| (0..8).contains(x) && *x != file)
+        .fold(0, |mask, x| mask | get_file_mask(x))
+}
+
+// Every square in front of the given rank from the point of view of the color
+fn get_ranks_ahead_mask(rank: i32, color: Color) -> u64 {
+    match color {
+        Color::White => ((0xFF << (rank + 1)) & 0xFF) * RANK_1,
+        Color::Black => ((1 << rank) - 1) * RANK_1,
+    }
+}
+
+fn get_material_and_squares(board: &Board, color: Color) -> (Score, Score) {
+    let mut material = Score::default();
+    let mut piece_squares = Score::default();
+
+    for kind in KINDS {
+        for square in bitboard::squares(board.get_pieces(color, kind)) {
+            material.add(get_material(kind), 1);
+            piece_squares.add(get_piece_square(kind, color, square), 1);
+        }
+    }
+
+    (material, piece_squares)
+}
+
+fn get_mobility(board: &Board, color: Color) -> Score {
+    let mut mobility = Score::default();
+    let own = board.get_occupancy(color);
+
+    for kind in [PieceKind::Knight, PieceKind::Bishop, PieceKind::Rook, PieceKind::Queen] {
+        for square in bitboard::squares(board.get_pieces(color, kind)) {
+            let reachable = get_attacks(board, kind, color, square) & !own;
+
+            mobility.add(get_mobility_weight(kind), reachable.count_ones() as i32);
+        }
+    }
+
+    mobility
+}
+
+fn get_king_safety(board: &Board, color: Color) -> Score {
+    let mut king_safety = Score::default();
+
+    let Some(king) = board.find_king(color) else {
+        return king_safety;
+    };
+
+    // Pawns on the two ranks in front of the king shelter it
+    let ahead = get_ranks_ahead_mask(king.get_rank(), color);
+    let near = match color {
+        Color::White => ahead & !get_ranks_ahead_mask(king.get_rank() + 2, color),
+        Color::Black => ahead & !get_ranks_ahead_mask((king.get_rank() - 2).max(0), color),
+    };
+    let shield = near & (get_file_mask(king.get_file()) | get_adjacent_files_mask(king.get_file()));
+
+    king_safety.add(PAWN_SHIELD_BONUS, (board.get_pieces(color, PieceKind::Pawn) & shield).count_ones() as i32);
+
+    let zone = bitboard::king_attacks(king) | bitboard::square_bit(king);
+    let enemy = color.opposite();
+
+    for kind in [PieceKind::Knight, PieceKind::Bishop, PieceKind::Rook, PieceKind::Queen] {
+        for square in bitboard::squares(board.get_pieces(enemy, kind)) {
+            let hits = get_attacks(board, kind, enemy, square) & zone;
+
+            king_safety.add(get_king_attack_weight(kind), hits.count_ones() as i32);
+        }
+    }
+
+    king_safety
+}
+
+fn get_pawn_structure(board: &Board, color: Color) -> Score {
+    let mut pawn_structure = Score::default();
+    let pawns = board.get_pieces(color, PieceKind::Pawn);
+    let enemy_pawns = board.get_pieces(color.opposite(), PieceKind::Pawn);
+
+    for file in 0..8 {
+        let count = (pawns & get_file_mask(file)).count_ones() as i32;
+
+        if count > 1 {
+            pawn_structure.add(DOUBLED_PAWN_PENALTY, count - 1);
+        }
+
+        if count > 0 && pawns & get_adjacent_files_mask(file) == 0 {
+            pawn_structure.add(ISOLATED_PAWN_PENALTY, count);
+        }
+    }
+
+    for square in bitboard::squares(pawns) {
+        let file = square.get_file();
+        let front = (get_file_mask(file) | get_adjacent_files_mask(file)) & get_ranks_ahead_mask(square.get_rank(), color);
+
+        if enemy_pawns & front == 0 {
+            let relative_rank = match color {
+                Color::White => square.get_rank(),
+                Color::Black => 7 - square.get_rank(),
+            };
+
+            pawn_structure.add(PASSED_PAWN_BONUS[relative_rank as usize], 1);
+        }
+    }
+
+    pawn_structure
+}
+
+pub fn evaluate(board: &Board) -> Evaluation {
+    let phase = KINDS
+        .iter()
+        .map(|&kind| {
+            let count = board.get_pieces(Color::White, kind) | board.get_pieces(Color::Black, kind);
+
+            count.count_ones() as i32 * get_phase_weight(kind)
+        })
+        .sum::<i32>()
+        .min(MAX_PHASE);
+
+    let (white_material, white_squares) = get_material_and_squares(board, Color::White);
+    let (black_material, black_squares) = get_material_and_squares(board, Color::Black);
+
+    Evaluation {
+        material: Term::new(white_material, black_material, phase),
+        piece_squares: Term::new(white_squares, black_squares, phase),
+        mobility: Term::new(get_mobility(board, Color::White), get_mobility(board, Color::Black), phase),
+        king_safety: Term::new(get_king_safety(board, Color::White), get_king_safety(board, Color::Black), phase),
+        pawn_structure: Term::new(get_pawn_structure(board, Color::White), get_pawn_structure(board, Color::Black), phase),
+        phase,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate, MAX_PHASE};
+    use crate::{board::Board, pieces::Color};
+
+    // The same position with the colours swapped and the board turned upside down
+    fn mirror(fen: &str) -> String {
+        let fields = fen.split_whitespace().collect::<Vec<&str>>();
+        let placement = fields[0]
+            .split('/')
+            .rev()
+            .map(|rank| rank.chars().map(|c| if c.is_ascii_uppercase() { c.to_ascii_lowercase() } else { c.to_ascii_uppercase() }).collect())
+            .collect::<Vec<String>>()
+            .join("/");
+        let turn = if fields[1] == "w" { "b" } else { "w" };
+
+        format!("{} {} - - 0 1", placement, turn)
+    }
+
+    #[test]
+    fn starting_position_is_balanced() {
+        let evaluation = evaluate(&Board::starting_position());
+
+        assert_eq!(evaluation.phase, MAX_PHASE);
+        assert_eq!(evaluation.get_total(), 0);
+        assert!(evaluation.get_terms().iter().all(|(_, term)| term.get_balance() == 0));
+    }
+
+    #[test]
+    fn mirrored_positions_swap_every_term() {
+        let fens = [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w - - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        ];
+
+        for fen in fens {
+            let evaluation = evaluate(&Board::from_fen(fen).unwrap());
+            let mirrored = evaluate(&Board::from_fen(&mirror(fen)).unwrap());
+
+            for ((name, term), (_, mirrored_term)) in evaluation.get_terms().into_iter().zip(mirrored.get_terms()) {
+                assert_eq!((term.white, term.black), (mirrored_term.black, mirrored_term.white), "{} of {}", name, fen);
+            }
+
+            assert_eq!(evaluation.get_score(Color::White), mirrored.get_score(Color::Black));
+        }
+    }
+
+    #[test]
+    fn breakdown_adds_up_to_the_total() {
+        // Black is a knight down
+        let evaluation = evaluate(&Board::from_fen("r1bqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap());
+        let report = evaluation.to_string();
+
+        assert!(evaluation.material.get_balance() > 0);
+        assert_eq!(evaluation.phase, MAX_PHASE - 1);
+        assert_eq!(evaluation.get_terms().iter().map(|(_, term)| term.get_balance()).sum::<i32>(), evaluation.get_total());
+
+        for (name, _) in evaluation.get_terms() {
+            assert!(report.contains(name), "{} is missing from the report", name);
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{board::Board, square::Square, pieces::{Piece, PieceKind, Color}, castling::{CastlingRights, CastlingSide}, error::FenError};
+
+pub const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+impl Board {
+    pub fn starting_position() -> Board {
+        Board::from_fen(STARTING_FEN).expect("the starting position is a valid FEN")
+    }
+
+    pub fn from_fen(fen: &str) -> Result<Board, FenError> {
+        let mut fields = fen.split_whitespace();
+
+        let placement = fields.next().ok_or(FenError::MissingPiecePlacement)?;
+        let turn = fields.next().ok_or(FenError::MissingSideToMove)?;
+        let castling = fields.next().ok_or(FenError::MissingCastlingRights)?;
+        let en_passant = fields.next().ok_or(FenError::MissingEnPassant)?;
+        // The move counters are optional, as in EPD records
+        let halfmove_clock = fields.next().unwrap_or("0");
+        let fullmove_number = fields.next().unwrap_or("1");
+
+        if fields.next().is_some() {
+            return Err(FenError::TooManyFields);
+        }
+
+        let mut board = Board::new();
+        let ranks = placement.split('/').collect::<Vec<&str>>();
+
+        if ranks.len() != 8 {
+            return Err(FenError::InvalidRankCount);
+        }
+
+        for (i, pieces) in ranks.iter().enumerate() {
+            let rank = 7 - i as i32;
+            let mut file = 0;
+
+            for c in pieces.chars() {
+                if let Some(skip) = c.to_digit(10) {
+                    if !(1..=8).contains(&skip) {
+                        return Err(FenError::InvalidEmptySquareCount);
+                    }
+
+                    file += skip as i32;
+                    continue;
+                }
+
+                if file > 7 {
+                    return Err(FenError::InvalidRankLength);
+                }
+
+                let piece = Piece::from_char(c).ok_or(FenError::InvalidPiece(c))?;
+
+                if matches!(piece, Piece::P(_)) && (rank == 0 || rank == 7) {
+                    return Err(FenError::PawnOnBackRank);
+                }
+
+                board.spawn(piece, Square::from_position((file, rank))).expect("each square is filled once");
+                file += 1;
+            }
+
+            if file != 8 {
+                return Err(FenError::InvalidRankLength);
+            }
+        }
+
+        match turn {
+            "w" => board.set_turn(Color::White),
+            "b" => board.set_turn(Color::Black),
+            _ => return Err(FenError::InvalidSideToMove),
+        }
+
+        let mut castling_rights = castling.parse::<CastlingRights>()?;
+
+        // A right needs its king and rook still at home; anything else could never be used and would only skew the hash
+        for color in [Color::White, Color::Black] {
+            let king_coord = match color {
+                Color::White => Square::E1,
+                Color::Black => Square::E8,
+            };
+            let is_at = |coord: Square, kind: PieceKind| board.get_piece(coord).is_some_and(|x| x.get_kind() == kind && x.get_color() == color);
+
+            for side in [CastlingSide::KingSide, CastlingSide::QueenSide] {
+                if !is_at(king_coord, PieceKind::King) || !is_at(side.get_rook_coord(color), PieceKind::Rook) {
+                    castling_rights.revoke(color, side);
+                }
+            }
+        }
+
+        board.set_castling_rights(castling_rights);
+
+        for color in [Color::White, Color::Black] {
+            if board.get_pieces(color, PieceKind::King).count_ones() != 1 {
+                return Err(FenError::InvalidKingCount);
+            }
+        }
+
+        // The side that just moved cannot have left its king in check
+        if board.is_in_check(board.get_turn().opposite()) {
+            return Err(FenError::OpponentInCheck);
+        }
+
+        if en_passant != "-" {
+            match en_passant.parse::<Square>() {
+                Ok(coord) if is_valid_en_passant(&board, coord) => board.set_en_passant(Some(coord)),
+                _ => return Err(FenError::InvalidEnPassant),
+            }
+        }
+
+        board.set_halfmove_clock(halfmove_clock.parse().map_err(|_| FenError::InvalidHalfmoveClock)?);
+
+        match fullmove_number.parse() {
+            Ok(fullmove_number) if fullmove_number > 0 => board.set_fullmove_number(fullmove_number),
+            _ => return Err(FenError::InvalidFullmoveNumber),
+        }
+
+        Ok(board)
+    }
+
+    pub fn to_fen(&self) -> String {
+        let mut placement = String::new();
+
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+
+            for file in 0..8 {
+                match self.get_piece(Square::from_position((file, rank))) {
+                    Some(piece) => {
+                        if empty > 0 {
+                            placement.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+
+                        placement.push(piece.to_char());
+                    },
+                    None => empty += 1,
+                }
+            }
+
+            if empty > 0 {
+                placement.push_str(&empty.to_string());
+            }
+
+            if rank > 0 {
+                placement.push('/');
+            }
+        }
+
+        let turn = match self.get_turn() {
+            Color::White => "w",
+            Color::Black => "b",
+        };
+
+        let en_passant = self.get_en_passant()
+            .map_or(String::from("-"), |coord| coord.to_string());
+
+        format!(
+            "{} {} {} {} {} {}",
+            placement,
+            turn,
+            self.get_castling_rights(),
+            en_passant,
+            self.get_halfmove_clock(),
+            self.get_fullmove_number(),
+        )
+    }
+}
+
+// The square a pawn of the side that just moved skipped over, with that pawn just past it
+fn is_valid_en_passant(board: &Board, coord: Square) -> bool {
+    let (rank, forward) = match board.get_turn() {
+        Color::White => (5, -1),
+        Color::Black => (2, 1),
+    };
+
+    if coord.get_rank() != rank {
+        return false;
+    }
+
+    let pawn = Square::from_position((coord.get_file(), rank + forward));
+    let origin = Square::from_position((coord.get_file(), rank - forward));
+
+    board.is_empty(coord) &&
+        board.is_empty(origin) &&
+        board.get_piece(pawn).is_some_and(|x| x.get_kind() == PieceKind::Pawn && x.get_color() != board.get_turn())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{board::Board, game::GameManager, moves::Move, error::FenError};
+
+    #[test]
+    fn round_trips_through_to_fen() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "rnbqkbnr/pppp1ppp/8/8/3Pp3/8/PPP1PPPP/RNBQKBNR b KQkq d3 0 2",
+        ];
+
+        for fen in fens {
+            assert_eq!(Board::from_fen(fen).unwrap().to_fen(), fen);
+        }
+    }
+
+    #[test]
+    fn drops_castling_rights_without_their_pieces() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w KQkq - 0 1").unwrap();
+
+        assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+        assert_eq!(board.get_hash(), Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap().get_hash());
+
+        let board = Board::from_fen("r3k3/8/8/8/8/8/8/R2K3R w KQkq - 0 1").unwrap();
+
+        assert_eq!(board.to_fen(), "r3k3/8/8/8/8/8/8/R2K3R w q - 0 1");
+    }
+
+    #[test]
+    fn rejects_impossible_positions() {
+        let invalid = [
+            // No black pawn stands on e5
+            ("4k3/8/8/3P4/8/8/8/4K3 w - e6 0 1", FenError::InvalidEnPassant),
+            // The square the pawn skipped over is not empty
+            ("4k3/8/4n3/3Pp3/8/8/8/4K3 w - e6 0 1", FenError::InvalidEnPassant),
+            // The en passant square belongs to the side to move
+            ("4k3/8/8/8/3pP3/8/8/4K3 w - e3 0 1", FenError::InvalidEnPassant),
+            ("8/8/8/8/8/8/8/4K3 w - - 0 1", FenError::InvalidKingCount),
+            ("4k3/8/8/8/8/8/8/3KK3 w - - 0 1", FenError::InvalidKingCount),
+            ("4k3/4R3/8/8/8/8/8/4K3 w - - 0 1", FenError::OpponentInCheck),
+        ];
+
+        for (fen, error) in invalid {
+            assert_eq!(Board::from_fen(fen).err(), Some(error), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn accepts_large_move_numbers() {
+        let mut game = GameManager::from_fen("4k3/8/8/8/8/8/8/4K2R b - - 0 4294967295").unwrap();
+
+        game.play(Move::from_uci("e8d8").unwrap()).unwrap();
+
+        // The counters stop at their largest value rather than wrap around
+        assert_eq!(game.to_fen(), "3k4/8/8/8/8/8/8/4K2R w - - 1 4294967295");
+
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K2R w - - 4294967295 1").unwrap();
+
+        board.make_move(Move::from_uci("h1h2").unwrap()).unwrap();
+        assert_eq!(board.get_halfmove_clock(), u32::MAX);
+    }
+}
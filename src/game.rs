@@ -1,63 +1,232 @@
-use crate::{board::Board, square::Square, pieces::{Piece, Color, MoveStatus, Pawn}};
+use std::time::Duration;
 
+use crate::{board::Board, square::Square, pieces::{Piece, PieceKind, Color, MoveStatus, Pawn}, evaluation::{self, Evaluation}, moves::{Move, Undo}, error::{MoveError, FenError}};
+
+#[derive(Clone)]
 pub enum GameState {
     Playing { turn: Color },
     InCheck { by_color: Color },
     Promoting { pawn: Pawn },
+    Checkmate { winner: Color },
+    Resigned { winner: Color },
+    Stalemate,
+    Draw { reason: DrawReason },
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DrawReason {
+    Agreement,
+    InsufficientMaterial,
+    // Claimed by a player
+    ThreefoldRepetition,
+    FiftyMoveRule,
+    // Drawn without a claim
+    FivefoldRepetition,
+    SeventyFiveMoveRule,
+}
+
+impl std::fmt::Display for DrawReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DrawReason::Agreement => write!(f, "agreement"),
+            DrawReason::InsufficientMaterial => write!(f, "insufficient material"),
+            DrawReason::ThreefoldRepetition => write!(f, "threefold repetition"),
+            DrawReason::FiftyMoveRule => write!(f, "the fifty-move rule"),
+            DrawReason::FivefoldRepetition => write!(f, "fivefold repetition"),
+            DrawReason::SeventyFiveMoveRule => write!(f, "the 75-move rule"),
+        }
+    }
 }
 
+#[derive(Clone)]
 pub struct GameManager {
     board: Board,
     state: GameState,
-    turn_count: u32,
     piece_selected: Option<Piece>,
+    history: Vec<Undo>,
+    // Hash of the position before each move of the history, to spot repetitions
+    positions: Vec<u64>,
+    // Moves taken back with `undo`, the most recent last
+    redo_stack: Vec<Move>,
+    // Remaining clock time after each move, when the game is played on a clock
+    clocks: Vec<Option<Duration>>,
+    tags: Vec<(String, String)>,
 }
 
 impl GameManager {
+    // An empty board has no moves for either side, so the game starts out stalemated
     pub fn new() -> Self {
-        Self {
+        let mut game = Self {
             board: Board::new(),
             state: GameState::Playing { turn: Color::White },
-            turn_count: 0,
             piece_selected: None,
+            history: Vec::new(),
+            positions: Vec::new(),
+            redo_stack: Vec::new(),
+            clocks: Vec::new(),
+            tags: Vec::new(),
+        };
+        game.update_state(Color::White);
+
+        game
+    }
+
+    pub fn standard() -> Self {
+        Self {
+            board: Board::starting_position(),
+            state: GameState::Playing { turn: Color::White },
+            piece_selected: None,
+            history: Vec::new(),
+            positions: Vec::new(),
+            redo_stack: Vec::new(),
+            clocks: Vec::new(),
+            tags: Vec::new(),
         }
     }
 
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        let board = Board::from_fen(fen)?;
+        let turn = board.get_turn();
+
+        let mut game = Self {
+            board,
+            state: GameState::Playing { turn },
+            piece_selected: None,
+            history: Vec::new(),
+            positions: Vec::new(),
+            redo_stack: Vec::new(),
+            clocks: Vec::new(),
+            tags: Vec::new(),
+        };
+        game.update_state(turn);
+
+        Ok(game)
+    }
+
+    pub fn to_fen(&self) -> String {
+        self.board.to_fen()
+    }
+
+    pub fn perft(&self, depth: u32) -> u64 {
+        self.board.clone().perft(depth)
+    }
+
+    pub fn divide(&self, depth: u32) -> Vec<(Move, u64)> {
+        self.board.clone().divide(depth)
+    }
+
+    pub fn evaluate(&self) -> Evaluation {
+        evaluation::evaluate(&self.board)
+    }
+
     pub fn get_turn(&self) -> Color {
         match self.state {
             GameState::Playing { turn } => turn,
             GameState::InCheck { by_color } => by_color,
             GameState::Promoting { ref pawn } => pawn.get_color(),
+            GameState::Checkmate { winner } | GameState::Resigned { winner } => winner.opposite(),
+            GameState::Stalemate | GameState::Draw { .. } => self.board.get_turn(),
         }
     }
 
-    pub fn get_board(&self) -> &Board {
-        &self.board
+    pub fn is_over(&self) -> bool {
+        matches!(
+            self.state,
+            GameState::Checkmate { .. } |
+            GameState::Resigned { .. } |
+            GameState::Stalemate |
+            GameState::Draw { .. }
+        )
+    }
+
+    // The side to move gives up
+    pub fn resign(&mut self) {
+        if !self.is_over() {
+            self.state = GameState::Resigned { winner: self.get_turn().opposite() };
+        }
+    }
+
+    pub fn agree_draw(&mut self) {
+        if !self.is_over() {
+            self.state = GameState::Draw { reason: DrawReason::Agreement };
+        }
+    }
+
+    // Ends a game the moves alone do not finish, as a recorded result does
+    pub(crate) fn set_result(&mut self, state: GameState) {
+        if !self.is_over() {
+            self.state = state;
+        }
+    }
+
+    // A draw the side to move may claim, though the game goes on until someone does
+    pub fn get_claimable_draw(&self) -> Option<DrawReason> {
+        if self.is_over() || matches!(self.state, GameState::Promoting { .. }) {
+            return None;
+        }
+
+        if self.get_repetitions() >= 3 {
+            Some(DrawReason::ThreefoldRepetition)
+        } else if self.board.get_halfmove_clock() >= 100 {
+            Some(DrawReason::FiftyMoveRule)
+        } else {
+            None
+        }
+    }
+
+    pub fn claim_draw(&mut self) -> Result<(), MoveError> {
+        let reason = self.get_claimable_draw().ok_or(MoveError::NoDrawToClaim)?;
+        self.state = GameState::Draw { reason };
+
+        Ok(())
+    }
+
+    // How many times the current position has stood on the board, this time included
+    pub fn get_repetitions(&self) -> usize {
+        let hash = self.board.get_hash();
+        // Nothing before the last capture or pawn move can come back
+        let reversible = (self.board.get_halfmove_clock() as usize).min(self.positions.len());
+
+        1 + self.positions[self.positions.len() - reversible..]
+            .iter()
+            .filter(|&&x| x == hash)
+            .count()
     }
 
-    pub fn get_board_mut(&mut self) -> &mut Board {
-        &mut self.board
+    pub(crate) fn get_positions(&self) -> &[u64] {
+        &self.positions
+    }
+
+    pub fn get_board(&self) -> &Board {
+        &self.board
     }
 
     pub fn get_state(&self) -> &GameState {
         &self.state
     }
 
-    pub fn get_state_mut(&mut self) -> &mut GameState {
-        &mut self.state
-    }
+    pub fn select_piece(&mut self, coord: Square) -> Result<(), MoveError> {
+        if self.is_over() {
+            return Err(MoveError::GameOver);
+        }
+
+        if let GameState::Promoting { .. } = self.state {
+            return Err(MoveError::PromotionRequired);
+        }
 
-    pub fn select_piece(&mut self, coord: Square) -> Result<(), &str> {
         let piece = self.board
-            .get_piece_mut(coord)
-            .ok_or("No piece found!")?;
+            .get_piece(coord)
+            .ok_or(MoveError::NoPieceOnSquare(coord))?
+            .clone();
         let color = piece.get_color();
 
-        self.piece_selected = Some(piece.clone());
-        self.piece_selected
-            .as_ref()
-            .unwrap()
-            .get_valid_moves(&mut self.board, coord)
+        if color != self.get_turn() {
+            return Err(MoveError::NotYourTurn);
+        }
+
+        self.piece_selected = Some(piece);
+        self.board
+            .get_legal_moves(coord)
             .iter()
             .for_each(|i| {
                 match i.1 {
@@ -74,8 +243,19 @@ impl GameManager {
         Ok(())
     }
 
-    pub fn move_piece(&mut self, coord_from: Square, coord_to: Square) -> Result<(), &str> {
-        let color = self.piece_selected.as_ref().unwrap().get_color();
+    pub fn move_piece(&mut self, coord_from: Square, coord_to: Square) -> Result<(), MoveError> {
+        if self.is_over() {
+            return Err(MoveError::GameOver);
+        }
+
+        if let GameState::Promoting { .. } = self.state {
+            return Err(MoveError::PromotionRequired);
+        }
+
+        let color = self.piece_selected
+            .as_ref()
+            .ok_or(MoveError::NoPieceSelected)?
+            .get_color();
 
         if coord_from == coord_to {
             self.board.clear_marks();
@@ -84,31 +264,215 @@ impl GameManager {
             return Ok(());
         }
 
-        let condition = self.piece_selected
-            .as_ref()
-            .unwrap()
-            .get_valid_moves(&mut self.board, coord_from)
+        match self.board.validate_move(Move::new(coord_from, coord_to, None)) {
+            // The promotion piece is chosen afterwards with `promote`
+            Ok(()) | Err(MoveError::PromotionRequired) => (),
+            Err(e) => return Err(e),
+        }
+
+        let hash = self.board.get_hash();
+        let undo = self.board.make_move(Move::new(coord_from, coord_to, None))?;
+        self.history.push(undo);
+        self.positions.push(hash);
+        self.redo_stack.clear();
+        self.piece_selected = None;
+        self.board.clear_marks();
+
+        // The turn passes only once the pawn reaching the last rank has been promoted
+        if let Some(Piece::P(pawn)) = self.board.get_piece(coord_to) {
+            if coord_to.get_rank() == 0 || coord_to.get_rank() == 7 {
+                self.state = GameState::Promoting { pawn: pawn.clone() };
+
+                return Ok(());
+            }
+        }
+
+        self.update_state(color.opposite());
+
+        Ok(())
+    }
+
+    pub fn promote(&mut self, kind: PieceKind) -> Result<(), MoveError> {
+        let GameState::Promoting { ref pawn } = self.state else {
+            return Err(MoveError::NoPromotionPending);
+        };
+
+        let color = pawn.get_color();
+
+        self.board.promote(pawn.get_coord(), kind)?;
+
+        // The move is replayed with its promotion piece on redo
+        if let Some(undo) = self.history.last_mut() {
+            undo.mv = Move::new(undo.mv.get_from(), undo.mv.get_to(), Some(kind));
+        }
+
+        self.update_state(color.opposite());
+
+        Ok(())
+    }
+
+    // Every legal move for the side to move, with one entry per promotion piece
+    pub fn get_legal_moves(&self) -> Vec<Move> {
+        if self.is_over() || matches!(self.state, GameState::Promoting { .. }) {
+            return Vec::new();
+        }
+
+        self.board.get_all_playable_moves()
+    }
+
+    // Selects, moves and promotes in one step, the way engines and replays hand moves over
+    pub fn play(&mut self, mv: Move) -> Result<(), MoveError> {
+        if self.is_over() {
+            return Err(MoveError::GameOver);
+        }
+
+        if let GameState::Promoting { .. } = self.state {
+            return Err(MoveError::PromotionRequired);
+        }
+
+        self.board.validate_move(mv)?;
+
+        self.select_piece(mv.get_from())?;
+        self.move_piece(mv.get_from(), mv.get_to())?;
+
+        if let Some(kind) = mv.get_promotion() {
+            self.promote(kind)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn get_san(&self, mv: Move) -> Result<String, MoveError> {
+        self.board.clone().get_san(mv)
+    }
+
+    pub fn parse_san(&self, san: &str) -> Result<Move, MoveError> {
+        self.board.parse_san(san)
+    }
+
+    pub fn play_san(&mut self, san: &str) -> Result<Move, MoveError> {
+        let mv = self.parse_san(san)?;
+
+        self.play(mv)?;
+
+        Ok(mv)
+    }
+
+    // Every move played from the starting position, in order
+    pub fn get_moves(&self) -> Vec<Move> {
+        self.history.iter().map(|x| x.get_move()).collect()
+    }
+
+    // The position before the first move of the history
+    pub fn get_initial_board(&self) -> Board {
+        let mut board = self.board.clone();
+
+        for undo in self.history.iter().rev() {
+            board.unmake_move(undo).expect("the history leads to the current position");
+        }
+
+        board
+    }
+
+    pub fn get_clocks(&self) -> &[Option<Duration>] {
+        &self.clocks
+    }
+
+    // Records the mover's remaining time for the last move played
+    pub fn set_clock(&mut self, remaining: Duration) -> Result<(), MoveError> {
+        if self.history.is_empty() {
+            return Err(MoveError::NoMovePlayed);
+        }
+
+        self.clocks.resize(self.history.len(), None);
+        self.clocks[self.history.len() - 1] = Some(remaining);
+
+        Ok(())
+    }
+
+    pub fn get_tags(&self) -> &[(String, String)] {
+        &self.tags
+    }
+
+    pub fn get_tag(&self, name: &str) -> Option<&str> {
+        self.tags
             .iter()
-            .any(|i| {
-                i.0 == coord_to && matches!(
-                    i.1,
-                    MoveStatus::Capturable {..} |
-                    MoveStatus::EnPassant {..} |
-                    MoveStatus::Movable {..} |
-                    MoveStatus::Castling {..}
-                )
-            });
+            .find(|x| x.0 == name)
+            .map(|x| x.1.as_str())
+    }
 
-        if !condition {
-            return Err("Invalid move!");
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|x| x.0 == name) {
+            Some(tag) => tag.1 = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
         }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.history.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub fn undo(&mut self) -> Result<(), MoveError> {
+        let undo = self.history.pop().ok_or(MoveError::NothingToUndo)?;
+        self.clocks.truncate(self.history.len());
+        self.positions.truncate(self.history.len());
 
-        self.piece_selected.as_mut().unwrap().move_to(&mut self.board, coord_to).unwrap();
-        self.piece_selected = None;
-        self.state = GameState::Playing { turn: color.opposite() };
-        self.turn_count += 1;
         self.board.clear_marks();
+        self.board.unmake_move(&undo)?;
+        self.redo_stack.push(undo.get_move());
+        self.piece_selected = None;
+        self.update_state(self.board.get_turn());
 
         Ok(())
     }
+
+    pub fn redo(&mut self) -> Result<(), MoveError> {
+        let mv = *self.redo_stack.last().ok_or(MoveError::NothingToRedo)?;
+
+        // Playing the move would otherwise discard the rest of the redo stack, and a refused move keeps all of it
+        let mut redo_stack = std::mem::take(&mut self.redo_stack);
+        let result = self.play(mv);
+
+        if result.is_ok() {
+            redo_stack.pop();
+        }
+
+        self.redo_stack = redo_stack;
+
+        result
+    }
+
+    fn update_state(&mut self, turn: Color) {
+        let has_legal_moves = !self.board.get_all_legal_moves(turn).is_empty();
+        let is_in_check = self.board.is_in_check(turn);
+
+        self.state = match (has_legal_moves, is_in_check) {
+            (false, true) => GameState::Checkmate { winner: turn.opposite() },
+            (false, false) => GameState::Stalemate,
+            (true, true) => GameState::InCheck { by_color: turn },
+            (true, false) => GameState::Playing { turn },
+        };
+
+        if self.is_over() {
+            return;
+        }
+
+        if self.board.is_insufficient_material() {
+            self.state = GameState::Draw { reason: DrawReason::InsufficientMaterial };
+        } else if self.get_repetitions() >= 5 {
+            self.state = GameState::Draw { reason: DrawReason::FivefoldRepetition };
+        } else if self.board.get_halfmove_clock() >= 150 {
+            self.state = GameState::Draw { reason: DrawReason::SeventyFiveMoveRule };
+        }
+    }
+}
+
+impl Default for GameManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
\ No newline at end of file
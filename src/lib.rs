@@ -0,0 +1,35 @@
+// Positions, moves and games with their notations, plus an engine to play them
+mod pieces;
+mod square;
+mod board;
+mod game;
+mod castling;
+mod moves;
+mod error;
+mod pgn;
+mod search;
+mod transposition;
+mod evaluation;
+mod uci;
+mod attacks;
+mod bitboard;
+mod fen;
+mod perft;
+mod san;
+mod zobrist;
+
+// The modules stay private; this is the whole public surface
+pub use attacks::AttackMap;
+pub use board::{Board, Flipped};
+pub use castling::{CastlingRights, CastlingSide};
+pub use error::{FenError, MoveError};
+pub use evaluation::{Evaluation, Term};
+pub use fen::STARTING_FEN;
+pub use game::{DrawReason, GameManager, GameState};
+pub use moves::{Move, Undo};
+pub use pgn::{PgnError, PgnReader};
+pub use pieces::{Bishop, Color, King, Knight, Pawn, Piece, PieceKind, Queen, Rook};
+pub use search::{Search, SearchLimits, SearchResult};
+pub use square::Square;
+pub use transposition::TranspositionTable;
+pub use uci::Uci;
@@ -1,96 +1,320 @@
-mod pieces;
-mod square;
-mod board;
-mod game;
+use std::{env, fs::{self, File}, io::{self, BufReader, IsTerminal, Write}, time::{Instant, SystemTime, UNIX_EPOCH}};
 
-use std::io;
+use chess::{Color, Flipped, GameManager, GameState, Move, MoveError, PieceKind, PgnReader, Uci};
 
-use pieces::Piece::{P};
-use square::Square;
-use game::GameManager;
+const USAGE: &str = "usage: chess [--fen <FEN> | --pgn <file>]\n       chess (perft | divide) <depth> [--fen <FEN>]\n       chess uci";
 
-use crate::pieces::{Pawn, Color};
+const HELP: &str = "\
+Enter a move in SAN (e4, Nf3, O-O, e8=Q) or UCI notation (e2e4, e7e8q), or one of:
+  moves          list the legal moves
+  undo, redo     take back a move or play it again
+  flip           turn the board around
+  save <file>    write the game to a PGN file
+  draw           claim a draw by repetition or the fifty-move rule
+  resign         give up the game
+  help           show this list
+  quit           leave the game";
 
 fn main() {
-    let mut game = GameManager::new();
-    game.get_board_mut().spawn(P(Pawn::new(Color::White)), Square::C2).ok();
-    game.get_board_mut().spawn(P(Pawn::new(Color::Black)), Square::B4).ok();
-    // game.get_board_mut().spawn(R(Rook::new(Color::Black)), Square::B4).ok();
+    let args = env::args().skip(1).collect::<Vec<String>>();
 
-    // game.get_board_mut().clear_marks();
-    // board.move_piece(Square::A1, Square::A2).ok();
-    print!("{}", game.get_board());
+    match args.first().map(String::as_str) {
+        Some(command @ ("perft" | "divide")) => {
+            run_perft(command, &args[1..]);
+            return;
+        },
+        Some("uci") => {
+            Uci::new().run();
+            return;
+        },
+        _ => (),
+    }
 
-    let mut user_input = String::new();
+    // GUIs start engines without arguments and speak first, so a piped `uci` switches to the protocol
+    let mut first_line = None;
+
+    if args.is_empty() && !io::stdin().is_terminal() {
+        let mut line = String::new();
+
+        if io::stdin().read_line(&mut line).is_ok_and(|x| x > 0) {
+            if line.trim() == "uci" {
+                let mut uci = Uci::new();
+
+                uci.handle(&line);
+                uci.run();
+                return;
+            }
+
+            first_line = Some(line);
+        }
+    }
+
+    let Some(mut game) = load_game(&args) else {
+        return;
+    };
+
+    // A game read from a file keeps the date it was played on
+    if args.first().is_none_or(|x| x != "--pgn") {
+        game.set_tag("Date", &get_today());
+    }
+
+    let loaded_moves = game.get_moves();
+
+    play(&mut game, first_line);
+
+    // Every game played on is archived next to where it was played
+    if game.get_moves() != loaded_moves {
+        let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |x| x.as_secs());
+
+        save_pgn(&game, &format!("chess-{}.pgn", seconds));
+    }
+}
+
+// A line already read from the input, if any, is taken as the first command
+fn play(game: &mut GameManager, mut first_line: Option<String>) {
     let stdin = io::stdin();
+    let mut flipped = false;
+    let mut user_input = String::new();
+
+    print_board(game, flipped);
+
     loop {
-        match &game.get_state() {
-            game::GameState::Playing { .. } => {
-                loop {
-                    match game.get_turn() {
-                        Color::White => print!("White's turn, "),
-                        Color::Black => print!("Black's turn, "),
-                    }
-            
-                    println!("Select a piece: ");
-                    stdin.read_line(&mut user_input).expect("Failed to read line");
-                    let coord = Square::from_str(&user_input[0..2]);
-            
-                    if coord.is_none() {
-                        println!("invalid input");
-                        user_input.clear();
-                        continue;
-                    }
-            
-                    if game.select_piece(coord.unwrap()).is_err() {
-                        println!("invalid piece");
-                        user_input.clear();
-                        continue;
-                    }
-            
-                    print!("{}", game.get_board());
-                    user_input.clear();
-                    
-                    loop {
-                        println!("move the piece to: ");
-                        stdin.read_line(&mut user_input).unwrap();
-                        if game.move_piece(coord.unwrap(), Square::from_str(&user_input[0..2]).unwrap()).is_err() {
-                            user_input.clear();
-                            continue;
-                        };
-                
-                        user_input.clear();
-                        break;
-                    }
-                    
-                    print!("{}", game.get_board());
+        match game.get_state() {
+            GameState::Checkmate { winner } => {
+                println!("Checkmate! {} wins.", get_color_name(*winner));
+                break;
+            },
+            GameState::Resigned { winner } => {
+                println!("{} resigns. {} wins.", get_color_name(winner.opposite()), get_color_name(*winner));
+                break;
+            },
+            GameState::Stalemate => {
+                println!("Stalemate! The game is drawn.");
+                break;
+            },
+            GameState::Draw { reason } => {
+                println!("The game is drawn by {}.", reason);
+                break;
+            },
+            GameState::InCheck { .. } => println!("Check!"),
+            GameState::Playing { .. } => (),
+            GameState::Promoting { .. } => println!("Choose a piece to promote to: q, r, b or n"),
+        }
 
-                }
+        if let Some(reason) = game.get_claimable_draw() {
+            println!("You may claim a draw by {}, type draw to do so.", reason);
+        }
+
+        print!("{} to move> ", get_color_name(game.get_turn()));
+        io::stdout().flush().ok();
+
+        user_input.clear();
+
+        let read = match first_line.take() {
+            Some(line) => {
+                user_input.push_str(&line);
+                Ok(line.len())
             },
-            game::GameState::InCheck { by_color: _ } => {
-                
-            },
-            game::GameState::Promoting { ref pawn } => {
-                loop {
-                    println!("select a piece to promote to: (Q, R, B, N) ");
-                    stdin.read_line(&mut user_input).unwrap();
-
-                    match user_input.trim() {
-                        "Q" | "q" => { pawn.try_into_queen(); break; },
-                        "R" | "r" => { pawn.try_into_rook(); break; },
-                        "B" | "b" => { pawn.try_into_bishop(); break; },
-                        "N" | "n" => { pawn.try_into_knight(); break; },
-                        _ => {
-                            println!("invalid input");
-                            user_input.clear();
-                            continue;
-                        }
-                    };
-                }
+            None => stdin.read_line(&mut user_input),
+        };
 
-                user_input.clear();
-                
+        match read {
+            // The end of the input quits like `quit` does
+            Ok(0) => {
+                println!();
+                break;
+            },
+            Ok(_) => (),
+            Err(e) => {
+                println!("Could not read the input: {}", e);
+                continue;
             },
         }
+
+        let tokens = user_input.split_whitespace().collect::<Vec<&str>>();
+
+        let result = match tokens[..] {
+            [] => continue,
+            ["help"] => {
+                println!("{}", HELP);
+                continue;
+            },
+            ["moves"] => {
+                let moves = game.get_legal_moves()
+                    .into_iter()
+                    .filter_map(|x| game.get_san(x).ok())
+                    .collect::<Vec<String>>();
+
+                println!("{}", moves.join(" "));
+                continue;
+            },
+            ["save", path] => {
+                save_pgn(game, path);
+                continue;
+            },
+            ["save"] => {
+                println!("Name the file to save to, such as save game.pgn");
+                continue;
+            },
+            ["quit"] => break,
+            ["flip"] => {
+                flipped = !flipped;
+                Ok(())
+            },
+            ["draw"] => game.claim_draw(),
+            ["resign"] => {
+                game.resign();
+                Ok(())
+            },
+            ["undo"] => game.undo(),
+            ["redo"] => game.redo(),
+            [input] if matches!(game.get_state(), GameState::Promoting { .. }) => {
+                match input.chars().next().and_then(PieceKind::from_char) {
+                    Some(kind) if input.len() == 1 && PieceKind::PROMOTIONS.contains(&kind) => game.promote(kind),
+                    _ => {
+                        println!("Choose q, r, b or n");
+                        continue;
+                    },
+                }
+            },
+            [input] => parse_move(game, input).and_then(|x| game.play(x)),
+            _ => {
+                println!("Unknown command, type help for the list");
+                continue;
+            },
+        };
+
+        match result {
+            Ok(()) => print_board(game, flipped),
+            Err(e) => println!("{}", get_error_message(e)),
+        }
     }
-}
\ No newline at end of file
+}
+
+// Reads a move in UCI long algebraic notation, such as "e2e4" or "e7e8q", or else in SAN
+fn parse_move(game: &GameManager, input: &str) -> Result<Move, MoveError> {
+    match Move::from_uci(input) {
+        Some(mv) => Ok(mv),
+        None => game.parse_san(input),
+    }
+}
+
+// Words the failures the way a player at the prompt needs to hear them
+fn get_error_message(error: MoveError) -> String {
+    match error {
+        MoveError::NoPieceOnSquare(square) => format!("There is no piece on {}", square),
+        MoveError::NotYourTurn => String::from("That piece belongs to the other side"),
+        MoveError::KingInCheck => String::from("Illegal move, your king would be in check"),
+        MoveError::PromotionRequired => String::from("Name the promotion piece, such as e7e8q"),
+        MoveError::InvalidNotation => String::from("Not a move or a command, type help for the list"),
+        error => error.to_string(),
+    }
+}
+
+fn print_board(game: &GameManager, flipped: bool) {
+    match flipped {
+        true => print!("{}", Flipped(game.get_board())),
+        false => print!("{}", game.get_board()),
+    }
+}
+
+fn get_color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "White",
+        Color::Black => "Black",
+    }
+}
+
+fn load_game(args: &[String]) -> Option<GameManager> {
+    match args.first().map(String::as_str) {
+        None => Some(GameManager::standard()),
+        Some("--fen") => match GameManager::from_fen(&args[1..].join(" ")) {
+            Ok(game) => Some(game),
+            Err(e) => {
+                println!("invalid FEN: {}", e);
+                None
+            },
+        },
+        // Continues from the end of the first game in the file
+        Some("--pgn") if args.len() == 2 => {
+            let file = match File::open(&args[1]) {
+                Ok(file) => file,
+                Err(e) => {
+                    println!("could not open {}: {}", args[1], e);
+                    return None;
+                },
+            };
+
+            match PgnReader::new(BufReader::new(file)).next() {
+                Some(Ok(game)) => Some(game),
+                Some(Err(e)) => {
+                    println!("invalid PGN: {}", e);
+                    None
+                },
+                None => {
+                    println!("no game found in {}", args[1]);
+                    None
+                },
+            }
+        },
+        Some(_) => {
+            println!("{}", USAGE);
+            None
+        },
+    }
+}
+
+fn run_perft(command: &str, args: &[String]) {
+    let Some(depth) = args.first().and_then(|x| x.parse::<u32>().ok()) else {
+        println!("{}", USAGE);
+        return;
+    };
+
+    let Some(game) = load_game(&args[1..]) else {
+        return;
+    };
+
+    let start = Instant::now();
+    let nodes = match command {
+        "divide" => {
+            let moves = game.divide(depth);
+            for (name, count) in &moves {
+                println!("{}: {}", name, count);
+            }
+            println!();
+
+            moves.iter().map(|x| x.1).sum()
+        },
+        _ => game.perft(depth),
+    };
+
+    println!("Nodes searched: {}", nodes);
+    println!("Time: {:.3}s", start.elapsed().as_secs_f64());
+}
+
+fn save_pgn(game: &GameManager, path: &str) {
+    match fs::write(path, game.to_pgn()) {
+        Ok(()) => println!("Game saved to {}", path),
+        Err(e) => println!("Could not save the game: {}", e),
+    }
+}
+
+// Today's date in the "YYYY.MM.DD" form of the Date tag
+fn get_today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |x| x.as_secs() / 86400) as i64;
+
+    // Converts days since 1970-01-01 into a civil date, with years starting in March
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
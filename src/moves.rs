@@ -0,0 +1,81 @@
+use crate::{square::Square, pieces::{Piece, PieceKind}, castling::CastlingRights};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Move {
+    from: Square,
+    to: Square,
+    promotion: Option<PieceKind>,
+}
+
+impl Move {
+    pub fn new(from: Square, to: Square, promotion: Option<PieceKind>) -> Self {
+        Self { from, to, promotion }
+    }
+
+    // Long algebraic notation as UCI uses it, such as "e2e4" or "e7e8q"
+    pub fn from_uci(s: &str) -> Option<Move> {
+        if !s.is_ascii() || !(4..=5).contains(&s.len()) {
+            return None;
+        }
+
+        let from = s[0..2].parse::<Square>().ok()?;
+        let to = s[2..4].parse::<Square>().ok()?;
+        let promotion = match s[4..].chars().next() {
+            Some(c) => Some(PieceKind::from_char(c)?),
+            None => None,
+        };
+
+        Some(Move::new(from, to, promotion))
+    }
+
+    pub fn get_from(&self) -> Square {
+        self.from
+    }
+
+    pub fn get_to(&self) -> Square {
+        self.to
+    }
+
+    pub fn get_promotion(&self) -> Option<PieceKind> {
+        self.promotion
+    }
+}
+
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.from, self.to)?;
+
+        match self.promotion {
+            Some(kind) => write!(f, "{}", kind.to_char()),
+            None => Ok(()),
+        }
+    }
+}
+
+// Everything `Board::unmake_move` needs to take a move back
+#[derive(Clone, Debug)]
+pub struct Undo {
+    pub(crate) mv: Move,
+    // The piece as it stood before moving, so a promoted piece turns back into its pawn
+    pub(crate) piece: Piece,
+    pub(crate) captured: Option<(Piece, Square)>,
+    pub(crate) castling_rights: CastlingRights,
+    pub(crate) en_passant: Option<Square>,
+    pub(crate) halfmove_clock: u32,
+    pub(crate) fullmove_number: u32,
+    pub(crate) hash: u64,
+}
+
+impl Undo {
+    pub fn get_move(&self) -> Move {
+        self.mv
+    }
+
+    pub fn get_piece(&self) -> &Piece {
+        &self.piece
+    }
+
+    pub fn get_captured(&self) -> Option<&Piece> {
+        self.captured.as_ref().map(|x| &x.0)
+    }
+}
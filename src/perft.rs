@@ -0,0 +1,41 @@
+use crate::{board::Board, moves::Move};
+
+impl Board {
+    pub fn perft(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let legal_moves = self.get_all_playable_moves();
+
+        // Leaf nodes are only counted, never played
+        if depth == 1 {
+            return legal_moves.len() as u64;
+        }
+
+        legal_moves
+            .into_iter()
+            .map(|mv| self.perft_after(mv, depth - 1))
+            .sum()
+    }
+
+    pub fn divide(&mut self, depth: u32) -> Vec<(Move, u64)> {
+        if depth == 0 {
+            return Vec::new();
+        }
+
+        self.get_all_playable_moves()
+            .into_iter()
+            .map(|mv| (mv, self.perft_after(mv, depth - 1)))
+            .collect()
+    }
+
+    fn perft_after(&mut self, mv: Move, depth: u32) -> u64 {
+        let undo = self.make_move(mv).expect("legal moves can always be played");
+        let nodes = self.perft(depth);
+
+        self.unmake_move(&undo).expect("a move just made can be taken back");
+
+        nodes
+    }
+}
@@ -0,0 +1,452 @@
+use std::{io::BufRead, time::Duration};
+
+use crate::{game::{GameManager, GameState, DrawReason}, pieces::Color, fen::STARTING_FEN};
+
+// The Seven Tag Roster, in the order every PGN lists it, with the value of an unknown tag
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+const MAX_LINE_LENGTH: usize = 79;
+
+impl GameManager {
+    pub fn get_result(&self) -> &'static str {
+        match self.get_state() {
+            GameState::Checkmate { winner: Color::White } | GameState::Resigned { winner: Color::White } => "1-0",
+            GameState::Checkmate { winner: Color::Black } | GameState::Resigned { winner: Color::Black } => "0-1",
+            GameState::Stalemate | GameState::Draw { .. } => "1/2-1/2",
+            _ => "*",
+        }
+    }
+
+    // Replays the first game of a PGN text
+    pub fn from_pgn(pgn: &str) -> Result<GameManager, PgnError> {
+        PgnReader::new(pgn.as_bytes())
+            .next()
+            .unwrap_or(Err(PgnError { game: 1, ply: 0, message: String::from("no game found") }))
+    }
+
+    pub fn to_pgn(&self) -> String {
+        let mut pgn = String::new();
+        let mut board = self.get_initial_board();
+        let initial_fen = board.to_fen();
+        let result = self.get_result();
+
+        for (name, unknown) in SEVEN_TAG_ROSTER {
+            let value = match name {
+                "Result" => result,
+                _ => self.get_tag(name).unwrap_or(unknown),
+            };
+
+            pgn.push_str(&format_tag(name, value));
+        }
+
+        if initial_fen != STARTING_FEN {
+            pgn.push_str(&format_tag("SetUp", "1"));
+            pgn.push_str(&format_tag("FEN", &initial_fen));
+        }
+
+        for (name, value) in self.get_tags() {
+            if !SEVEN_TAG_ROSTER.iter().any(|x| x.0 == name) && name != "SetUp" && name != "FEN" {
+                pgn.push_str(&format_tag(name, value));
+            }
+        }
+
+        pgn.push('\n');
+
+        let mut tokens = Vec::new();
+
+        for (ply, mv) in self.get_moves().into_iter().enumerate() {
+            // A move still waiting for its promotion piece has no SAN yet
+            let Ok(san) = board.get_san(mv) else {
+                break;
+            };
+
+            match board.get_turn() {
+                Color::White => tokens.push(format!("{}.", board.get_fullmove_number())),
+                Color::Black if ply == 0 => tokens.push(format!("{}...", board.get_fullmove_number())),
+                Color::Black => (),
+            }
+
+            tokens.push(san);
+
+            if let Some(Some(clock)) = self.get_clocks().get(ply) {
+                tokens.push(format!("{{[%clk {}]}}", format_clock(*clock)));
+            }
+
+            board.make_move(mv).expect("the history only holds legal moves");
+        }
+
+        tokens.push(result.to_string());
+
+        // Export format keeps movetext lines under 80 characters
+        let mut line = String::new();
+
+        for token in tokens {
+            if !line.is_empty() && line.len() + 1 + token.len() > MAX_LINE_LENGTH {
+                pgn.push_str(&line);
+                pgn.push('\n');
+                line.clear();
+            }
+
+            if !line.is_empty() {
+                line.push(' ');
+            }
+
+            line.push_str(&token);
+        }
+
+        pgn.push_str(&line);
+        pgn.push('\n');
+
+        pgn
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct PgnError {
+    // Both count from 1; ply 0 means the error came before the first move
+    pub game: usize,
+    pub ply: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for PgnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "game {}, ply {}: {}", self.game, self.ply, self.message)
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+// Reads one game at a time, so files with thousands of games never sit in memory at once
+pub struct PgnReader<R: BufRead> {
+    reader: R,
+    game_index: usize,
+    // The first tag of the next game, when a game ended without a result token
+    next_line: Option<String>,
+}
+
+impl<R: BufRead> PgnReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            game_index: 0,
+            next_line: None,
+        }
+    }
+
+    fn read_line(&mut self) -> Option<Result<String, PgnError>> {
+        if let Some(line) = self.next_line.take() {
+            return Some(Ok(line));
+        }
+
+        let mut line = String::new();
+
+        match self.reader.read_line(&mut line) {
+            Ok(0) => None,
+            Ok(_) => Some(Ok(line)),
+            Err(e) => Some(Err(PgnError { game: self.game_index + 1, ply: 0, message: e.to_string() })),
+        }
+    }
+
+    fn replay(&self, tags: Vec<(String, String)>, sans: Vec<String>) -> Result<GameManager, PgnError> {
+        let error = |ply: usize, message: String| PgnError { game: self.game_index, ply, message };
+
+        let mut game = match tags.iter().find(|x| x.0 == "FEN") {
+            Some((_, fen)) => GameManager::from_fen(fen).map_err(|e| error(0, format!("invalid FEN: {}", e)))?,
+            None => GameManager::standard(),
+        };
+
+        for (name, value) in &tags {
+            game.set_tag(name, value);
+        }
+
+        for (ply, san) in sans.iter().enumerate() {
+            let mv = game
+                .parse_san(san)
+                .map_err(|e| error(ply + 1, format!("{} '{}'", e, san)))?;
+
+            game.play(mv).map_err(|e| error(ply + 1, format!("{} '{}'", e, san)))?;
+        }
+
+        // A result the final position does not show came from a resignation, the clock or an agreement
+        match game.get_tag("Result") {
+            Some("1-0") => game.set_result(GameState::Resigned { winner: Color::White }),
+            Some("0-1") => game.set_result(GameState::Resigned { winner: Color::Black }),
+            Some("1/2-1/2") => game.set_result(GameState::Draw { reason: DrawReason::Agreement }),
+            _ => (),
+        }
+
+        Ok(game)
+    }
+}
+
+impl<R: BufRead> Iterator for PgnReader<R> {
+    type Item = Result<GameManager, PgnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut tags = Vec::new();
+        let mut movetext = Movetext::default();
+        let mut has_content = false;
+
+        while let Some(line) = self.read_line() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            let trimmed = line.trim();
+
+            if !movetext.in_comment && trimmed.starts_with('[') {
+                // A game without a result token ends where the next one's tags begin
+                if movetext.has_started {
+                    self.next_line = Some(line);
+                    break;
+                }
+
+                if let Some(tag) = parse_tag(trimmed) {
+                    tags.push(tag);
+                }
+
+                has_content = true;
+                continue;
+            }
+
+            // Lines starting with '%' are escaped for other programs
+            if trimmed.is_empty() && !movetext.in_comment || trimmed.starts_with('%') {
+                continue;
+            }
+
+            has_content = true;
+
+            if movetext.feed(trimmed) {
+                break;
+            }
+        }
+
+        if !has_content {
+            return None;
+        }
+
+        self.game_index += 1;
+
+        if let Some(message) = movetext.get_error() {
+            return Some(Err(PgnError { game: self.game_index, ply: movetext.sans.len(), message: message.to_string() }));
+        }
+
+        // The result token stands in for a missing Result tag
+        if let Some(result) = movetext.result.filter(|_| !tags.iter().any(|x| x.0 == "Result")) {
+            tags.push((String::from("Result"), result));
+        }
+
+        Some(self.replay(tags, movetext.sans))
+    }
+}
+
+// Collects the mainline moves, skipping comments, NAGs and variations
+#[derive(Default)]
+struct Movetext {
+    sans: Vec<String>,
+    in_comment: bool,
+    variation_depth: usize,
+    has_started: bool,
+    // The result token closing the movetext, if it had one
+    result: Option<String>,
+    error: Option<&'static str>,
+}
+
+impl Movetext {
+    // Returns true once the result token ends the game
+    fn feed(&mut self, line: &str) -> bool {
+        let mut word = String::new();
+
+        self.has_started = true;
+
+        for c in line.chars().chain(std::iter::once(' ')) {
+            if self.in_comment {
+                self.in_comment = c != '}';
+                continue;
+            }
+
+            if c.is_whitespace() || matches!(c, '{' | '}' | '(' | ')' | ';') {
+                if self.take_word(&word) {
+                    return true;
+                }
+
+                word.clear();
+            }
+
+            match c {
+                '{' => self.in_comment = true,
+                '(' => self.variation_depth += 1,
+                ')' => match self.variation_depth {
+                    0 => self.error = self.error.or(Some("unmatched ')'")),
+                    _ => self.variation_depth -= 1,
+                },
+                // The rest of the line is a comment
+                ';' => break,
+                '}' => self.error = self.error.or(Some("unmatched '}'")),
+                c if !c.is_whitespace() => word.push(c),
+                _ => (),
+            }
+        }
+
+        false
+    }
+
+    fn take_word(&mut self, word: &str) -> bool {
+        if word.is_empty() || self.variation_depth > 0 || word.starts_with('$') {
+            return false;
+        }
+
+        if matches!(word, "1-0" | "0-1" | "1/2-1/2" | "*") {
+            self.result = Some(word.to_string());
+
+            return true;
+        }
+
+        // Move numbers such as "12." or "12..." may run straight into the move
+        let san = match word.find('.') {
+            Some(i) if word[..i].chars().all(|c| c.is_ascii_digit()) => word[i..].trim_start_matches('.'),
+            _ => word,
+        };
+
+        if !san.is_empty() && !san.chars().all(|c| c.is_ascii_digit()) {
+            self.sans.push(san.to_string());
+        }
+
+        false
+    }
+
+    fn get_error(&self) -> Option<&'static str> {
+        match (self.error, self.in_comment, self.variation_depth) {
+            (Some(error), _, _) => Some(error),
+            (None, true, _) => Some("unterminated comment"),
+            (None, false, 0) => None,
+            (None, false, _) => Some("unterminated variation"),
+        }
+    }
+}
+
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?.trim();
+    let (name, value) = inner.split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+
+    Some((name.to_string(), value.replace("\\\"", "\"").replace("\\\\", "\\")))
+}
+
+fn format_tag(name: &str, value: &str) -> String {
+    format!("[{} \"{}\"]\n", name, value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn format_clock(clock: Duration) -> String {
+    let seconds = clock.as_secs();
+
+    format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PgnReader;
+    use crate::{game::GameManager, moves::Move, error::MoveError};
+
+    fn get_moves(game: &GameManager) -> Vec<String> {
+        game.get_moves().iter().map(Move::to_string).collect()
+    }
+
+    #[test]
+    fn skips_comments_nags_and_variations() {
+        let pgn = "1. e4 {best by test} e5 $1 2. Nf3 (2. f4 exf4 (2... d5) 3. Nf3) ; rest of line\n2... Nc6 3.Bb5 *";
+        let game = GameManager::from_pgn(pgn).unwrap();
+
+        assert_eq!(get_moves(&game), ["e2e4", "e7e5", "g1f3", "b8c6", "f1b5"]);
+    }
+
+    #[test]
+    fn reads_a_stream_of_games() {
+        let pgn = "[Event \"First\"]\n\n1. e4 e5 1-0\n\n[Event \"Second\"]\n\n1. d4\n[Event \"Third\"]\n\n1. c4 *\n";
+        let games = PgnReader::new(pgn.as_bytes()).collect::<Result<Vec<GameManager>, _>>().unwrap();
+
+        assert_eq!(games.len(), 3);
+        assert_eq!(games[0].get_tag("Event"), Some("First"));
+        // The result token stands in for the missing tag
+        assert_eq!(games[0].get_tag("Result"), Some("1-0"));
+        assert!(games[0].to_pgn().ends_with("1. e4 e5 1-0\n"));
+        assert_eq!(get_moves(&games[1]), ["d2d4"]);
+        assert_eq!(games[2].get_tag("Event"), Some("Third"));
+    }
+
+    #[test]
+    fn reports_the_game_and_ply_of_an_error() {
+        let pgn = "1. e4 e5 *\n\n1. d4 d5 2. Ke3 *\n\n1. e4 {open *\n";
+        let results = PgnReader::new(pgn.as_bytes()).collect::<Vec<_>>();
+
+        assert!(results[0].is_ok());
+
+        let error = results[1].clone().err().unwrap();
+
+        assert_eq!((error.game, error.ply), (2, 3));
+
+        let error = results[2].clone().err().unwrap();
+
+        assert_eq!((error.game, error.message.as_str()), (3, "unterminated comment"));
+        assert_eq!(results.len(), 3);
+    }
+
+    #[test]
+    fn exported_games_read_back_the_same() {
+        let mut game = GameManager::standard();
+
+        game.set_tag("Event", "Club \"Open\"");
+        game.set_tag("Annotator", "Nobody");
+
+        for san in ["e4", "d5", "exd5", "Qxd5", "Nc3", "Qa5", "d4", "Nf6", "Nf3", "Bf5", "Bc4", "e6", "O-O", "Nbd7"] {
+            game.play_san(san).unwrap();
+        }
+
+        game.resign();
+
+        let pgn = game.to_pgn();
+        let read = GameManager::from_pgn(&pgn).unwrap();
+
+        assert_eq!(read.get_moves(), game.get_moves());
+        assert_eq!(read.get_tag("Event"), Some("Club \"Open\""));
+        assert_eq!(read.get_tag("Result"), Some("0-1"));
+        assert_eq!(read.to_pgn(), pgn);
+
+        // A game set up from a position carries its FEN along, starting with Black's move
+        let mut game = GameManager::from_fen("8/8/8/8/8/4k3/p7/4K3 b - - 0 40").unwrap();
+
+        game.play_san("a1=Q#").unwrap();
+
+        let read = GameManager::from_pgn(&game.to_pgn()).unwrap();
+
+        assert!(game.to_pgn().ends_with("\n40... a1=Q# 0-1\n"));
+        assert_eq!(read.to_fen(), game.to_fen());
+        assert_eq!(read.to_pgn(), game.to_pgn());
+    }
+
+    #[test]
+    fn a_recorded_result_ends_the_game() {
+        let mut game = GameManager::from_pgn("[Result \"1-0\"]\n\n1. e4 e5 2. Nf3 1-0\n").unwrap();
+
+        assert!(game.is_over());
+        assert_eq!(game.play_san("Nc6"), Err(MoveError::GameOver));
+
+        // Taking a move back reopens the game, and the old result no longer applies
+        game.undo().unwrap();
+        game.play_san("Bc4").unwrap();
+        assert!(game.to_pgn().ends_with("\n1. e4 e5 2. Bc4 *\n"));
+
+        let game = GameManager::from_pgn("1. e4 e5 1/2-1/2").unwrap();
+
+        assert_eq!(game.get_result(), "1/2-1/2");
+    }
+}
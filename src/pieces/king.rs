@@ -1,4 +1,4 @@
-use crate::{square::Square, board::Board};
+use crate::{square::Square, board::Board, castling::CastlingSide, error::MoveError, bitboard};
 
 use super::{Color, MoveStatus};
 
@@ -6,8 +6,6 @@ use super::{Color, MoveStatus};
 pub struct King {
     color: Color,
     coord: Square,
-    is_checked: bool,
-    is_once_moved: bool,
 }
 
 impl King {
@@ -15,24 +13,10 @@ impl King {
         King {
             color,
             coord: Square::None,
-            is_checked: false,
-            is_once_moved: false,
         }
     }
 
-    pub fn is_checked(&self) -> bool {
-        self.is_checked
-    }
-
-    pub fn set_checked(&mut self, is_checked: bool) {
-        self.is_checked = is_checked;
-    }
-
-    pub fn set_once_moved(&mut self) {
-        self.is_once_moved = true;
-    }
-
-    pub fn set_coord(&mut self, coord: Square) {
+    pub(crate) fn set_coord(&mut self, coord: Square) {
         self.coord = coord;
     }
 
@@ -44,137 +28,52 @@ impl King {
         self.color
     }
 
-    pub fn get_valid_moves(&self, board: &mut Board, coord_from: Square) -> Vec<(Square, MoveStatus)> {
+    pub(crate) fn get_valid_moves(&self, board: &Board, coord_from: Square) -> Vec<(Square, MoveStatus)> {
         let mut valid_moves = Vec::new();
 
         let current_file = coord_from.get_file();
         let current_rank = coord_from.get_rank();
 
-        let direction = [
-            (-1, -1), (-1, 0), (-1, 1),
-            ( 0, -1),          ( 0, 1),
-            ( 1, -1), ( 1, 0), ( 1, 1)
-        ];
-
-        for (file, rank) in direction {
-            let dest_file = current_file + file;
-            let dest_rank = current_rank + rank;
-
-            if (0..8).contains(&dest_file) && (0..8).contains(&dest_rank) {
-                let position = Square::from_position((dest_file, dest_rank));
-                valid_moves.push((position, MoveStatus::Capturable { by_color: self.color, activated: false }));
-            }
+        for position in bitboard::squares(bitboard::king_attacks(coord_from)) {
+            valid_moves.push((position, MoveStatus::Capturable { by_color: self.color, activated: false }));
         }
         
-        match self.color {
-            Color::Black => {
-                if board.get_piece(Square::H8).is_some_and(|x| x.get_color() == self.color) &&
-                    board.is_empty(Square::F8) &&
-                    board.is_empty(Square::G8) &&
-                    !board.is_under_attack(Square::G8, Color::Black) &&
-                    !board.is_under_attack(Square::F8, Color::Black) &&
-                    !self.is_checked &&
-                    !self.is_once_moved
-                {
-                    valid_moves.push((Square::G8, MoveStatus::Castling { by_color: self.color, activated: false }));
-                    valid_moves.push((Square::F8, MoveStatus::Castling { by_color: self.color, activated: false }));
-                }
-            
-                if board.get_piece(Square::A8).is_some_and(|x| x.get_color() == self.color) &&
-                    board.is_empty(Square::B8) &&
-                    board.is_empty(Square::C8) &&
-                    board.is_empty(Square::D8) &&
-                    !board.is_under_attack(Square::B8, Color::Black) &&
-                    !board.is_under_attack(Square::C8, Color::Black) &&
-                    !board.is_under_attack(Square::D8, Color::Black) &&
-                    !self.is_checked &&
-                    !self.is_once_moved
-                {
-                    valid_moves.push((Square::B8, MoveStatus::Castling { by_color: self.color, activated: false }));
-                    valid_moves.push((Square::C8, MoveStatus::Castling { by_color: self.color, activated: false }));
-                    valid_moves.push((Square::D8, MoveStatus::Castling { by_color: self.color, activated: false }));
-                }
-            },
-            Color::White => {
-                if board.get_piece(Square::H1).is_some_and(|x| x.get_color() == self.color) &&
-                    board.is_empty(Square::F1) &&
-                    board.is_empty(Square::G1) &&
-                    !board.is_under_attack(Square::G1, Color::Black) &&
-                    !board.is_under_attack(Square::F1, Color::Black) &&
-                    !self.is_checked &&
-                    !self.is_once_moved
-                {
-                    valid_moves.push((Square::G1, MoveStatus::Castling { by_color: self.color, activated: false }));
-                    valid_moves.push((Square::F1, MoveStatus::Castling { by_color: self.color, activated: false }));
-                }
-                
-                if board.get_piece(Square::A1).is_some_and(|x| x.get_color() == self.color) &&
-                    board.is_empty(Square::B1) &&
-                    board.is_empty(Square::C1) &&
-                    board.is_empty(Square::D1) &&
-                    !board.is_under_attack(Square::B1, Color::Black) &&
-                    !board.is_under_attack(Square::C1, Color::Black) &&
-                    !board.is_under_attack(Square::D1, Color::Black) &&
-                    !self.is_checked &&
-                    !self.is_once_moved
-                {
-                    valid_moves.push((Square::B1, MoveStatus::Castling { by_color: self.color, activated: false }));
-                    valid_moves.push((Square::C1, MoveStatus::Castling { by_color: self.color, activated: false }));
-                    valid_moves.push((Square::D1, MoveStatus::Castling { by_color: self.color, activated: false }));
-                }
-            },
+        let home = match self.color {
+            Color::White => Square::E1,
+            Color::Black => Square::E8,
+        };
+
+        for side in [CastlingSide::KingSide, CastlingSide::QueenSide] {
+            let rook = side.get_rook_coord(self.color);
+            let (low, high) = if rook.get_file() > current_file {
+                (current_file + 1, rook.get_file())
+            } else {
+                (rook.get_file() + 1, current_file)
+            };
+            let is_path_clear = (low..high).all(|file| board.is_empty(Square::from_position((file, current_rank))));
+
+            if coord_from == home &&
+                board.get_castling_rights().can_castle(self.color, side) &&
+                board.get_piece(rook).is_some_and(|x| matches!(x, super::Piece::R(_)) && x.get_color() == self.color) &&
+                is_path_clear
+            {
+                valid_moves.push((side.get_king_target(self.color), MoveStatus::Castling { by_color: self.color, activated: false }));
+            }
         }
 
-        let opponent_move = board
-            .get_capture_board()
-            .iter()
-            .filter_map(|x| {
-                let color = match x.1 {
-                    MoveStatus::Capturable { by_color, .. } => by_color,
-                    MoveStatus::Threaten { by_color, .. } => by_color,
-                    MoveStatus::Pierced { by_color, .. } => by_color,
-                    MoveStatus::EnPassant { by_color, .. } => by_color,
-                    MoveStatus::Castling { by_color, .. } => by_color,
-                    MoveStatus::Movable { by_color, .. } => by_color,
-                    _ => todo!(),
-                };
-
-                if color == self.color.opposite() {
-                    Some(x.0)
-                } else {
-                    None
-                }
-            }).collect::<Vec<Square>>();
-
-        valid_moves.retain(|x| !opponent_move.contains(&x.0));
-        
         valid_moves
     }
 
-    pub fn move_to(&mut self, board: &mut Board, mut coord_to: Square) -> Result<(), &'static str> {
-        self.is_once_moved = true;
-
-        match self.color {
-            Color::Black => {
-                if self.coord == Square::E8 && coord_to == Square::G8 {
-                    board.move_piece(Square::H8, Square::F8).ok();
-                } else if self.coord == Square::E8 && coord_to == Square::C8 {
-                    board.move_piece(Square::A8, Square::D8).ok();
-                } else if self.coord == Square::E8 && coord_to == Square::B8 {
-                    board.move_piece(Square::A8, Square::D8).ok();
-                    coord_to = Square::C8;
-                }
-            },
-            Color::White => {
-                if self.coord == Square::E1 && coord_to == Square::G1 {
-                    board.move_piece(Square::H1, Square::F1).ok();
-                } else if self.coord == Square::E1 && coord_to == Square::C1 {
-                    board.move_piece(Square::A1, Square::D1).ok();
-                } else if self.coord == Square::E1 && coord_to == Square::B1 {
-                    board.move_piece(Square::A1, Square::D1).ok();
-                    coord_to = Square::C1;
-                }
-            },
+    pub(crate) fn move_to(&mut self, board: &mut Board, coord_to: Square) -> Result<(), MoveError> {
+        let home = match self.color {
+            Color::White => Square::E1,
+            Color::Black => Square::E8,
+        };
+
+        for side in [CastlingSide::KingSide, CastlingSide::QueenSide] {
+            if self.coord == home && coord_to == side.get_king_target(self.color) {
+                board.move_piece(side.get_rook_coord(self.color), side.get_rook_target(self.color))?;
+            }
         }
 
         board.move_piece(self.coord, coord_to)
@@ -1,4 +1,4 @@
-use crate::{board::Board, square::Square};
+use crate::{board::Board, square::Square, error::MoveError, bitboard};
 
 use super::{Color, MoveStatus};
 
@@ -16,7 +16,7 @@ impl Knight {
         }
     }
 
-    pub fn set_coord(&mut self, coord: Square) {
+    pub(crate) fn set_coord(&mut self, coord: Square) {
         self.coord = coord;
     }
 
@@ -28,45 +28,18 @@ impl Knight {
         self.color
     }
 
-    pub fn get_valid_moves(&self, board: &mut Board, coord_from: Square) -> Vec<(Square, MoveStatus)> {
+    pub(crate) fn get_valid_moves(&self, board: &Board, coord_from: Square) -> Vec<(Square, MoveStatus)> {
         let mut valid_moves = Vec::new();
+        let targets = bitboard::knight_attacks(coord_from) & !board.get_occupancy(self.color);
 
-        let current_file = coord_from.get_file();
-        let current_rank = coord_from.get_rank();
-    
-        let knight_moves = [
-            ( 2,  1), ( 2, -1), (-2,  1), (-2, -1),
-            ( 1,  2), ( 1, -2), (-1,  2), (-1, -2),
-        ];
-    
-        for &(file_offset, rank_offset) in &knight_moves {
-            let target_file = current_file + file_offset;
-            let target_rank = current_rank + rank_offset;
-    
-            if (0..8).contains(&target_file) && (0..8).contains(&target_rank) {
-                let position = Square::from_position((target_rank, target_file));
-                
-                if !board.is_empty(position) {
-                    let query = board.get_piece_mut(position).unwrap();
-                    let color = query.get_color();
-    
-                    if color != self.color {
-                        valid_moves.push((position, MoveStatus::Capturable { by_color: self.color, activated: false }));
-
-                        if let super::Piece::K(ref mut king) = query {
-                            king.set_checked(true);
-                        }
-                    }
-                } else {
-                    valid_moves.push((position, MoveStatus::Capturable { by_color: self.color, activated: false }));
-                }
-            }
+        for position in bitboard::squares(targets) {
+            valid_moves.push((position, MoveStatus::Capturable { by_color: self.color, activated: false }));
         }
-    
+
         valid_moves
     }
 
-    pub fn move_to(&mut self, board: &mut Board, coord_to: Square) -> Result<(), &'static str> {
+    pub(crate) fn move_to(&mut self, board: &mut Board, coord_to: Square) -> Result<(), MoveError> {
         board.move_piece(self.coord, coord_to)
     }
 }
\ No newline at end of file
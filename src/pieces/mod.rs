@@ -12,10 +12,10 @@ pub use rook::Rook;
 pub use queen::Queen;
 pub use king::King;
 
-use crate::{board::Board, square::Square};
+use crate::{board::Board, square::Square, error::MoveError, bitboard};
 
 #[derive(Clone, Copy, PartialEq)]
-pub enum MoveStatus {
+pub(crate) enum MoveStatus {
     None,
     Capturable { by_color: Color, activated: bool },
     Threaten { by_color: Color, activated: bool },
@@ -25,6 +25,43 @@ pub enum MoveStatus {
     Movable { by_color: Color, activated: bool },
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PieceKind {
+    Pawn,
+    Knight,
+    Bishop,
+    Rook,
+    Queen,
+    King,
+}
+
+impl PieceKind {
+    pub const PROMOTIONS: [PieceKind; 4] = [PieceKind::Queen, PieceKind::Rook, PieceKind::Bishop, PieceKind::Knight];
+
+    pub fn from_char(c: char) -> Option<PieceKind> {
+        match c.to_ascii_lowercase() {
+            'p' => Some(PieceKind::Pawn),
+            'n' => Some(PieceKind::Knight),
+            'b' => Some(PieceKind::Bishop),
+            'r' => Some(PieceKind::Rook),
+            'q' => Some(PieceKind::Queen),
+            'k' => Some(PieceKind::King),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            PieceKind::Pawn => 'p',
+            PieceKind::Knight => 'n',
+            PieceKind::Bishop => 'b',
+            PieceKind::Rook => 'r',
+            PieceKind::Queen => 'q',
+            PieceKind::King => 'k',
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum Piece {
     P(Pawn),
@@ -36,6 +73,40 @@ pub enum Piece {
 }
 
 impl Piece {
+    pub fn from_char(c: char) -> Option<Piece> {
+        let color = if c.is_ascii_uppercase() { Color::White } else { Color::Black };
+
+        match c.to_ascii_lowercase() {
+            'p' => Some(Piece::P(Pawn::new(color))),
+            'n' => Some(Piece::N(Knight::new(color))),
+            'b' => Some(Piece::B(Bishop::new(color))),
+            'r' => Some(Piece::R(Rook::new(color))),
+            'q' => Some(Piece::Q(Queen::new(color))),
+            'k' => Some(Piece::K(King::new(color))),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(&self) -> char {
+        let c = self.get_kind().to_char();
+
+        match self.get_color() {
+            Color::White => c.to_ascii_uppercase(),
+            Color::Black => c,
+        }
+    }
+
+    pub fn get_kind(&self) -> PieceKind {
+        match self {
+            Piece::P(_) => PieceKind::Pawn,
+            Piece::N(_) => PieceKind::Knight,
+            Piece::B(_) => PieceKind::Bishop,
+            Piece::R(_) => PieceKind::Rook,
+            Piece::Q(_) => PieceKind::Queen,
+            Piece::K(_) => PieceKind::King,
+        }
+    }
+
     pub fn get_color(&self) -> Color {
         match self {
             Piece::P(p) => p.get_color(),
@@ -47,7 +118,7 @@ impl Piece {
         }
     }
 
-    pub fn get_valid_moves(&self, board: &mut Board, coord: Square) -> Vec<(Square, MoveStatus)> {
+    pub(crate) fn get_valid_moves(&self, board: &Board, coord: Square) -> Vec<(Square, MoveStatus)> {
         match self {
             Piece::P(p) => p.get_valid_moves(board, coord),
             Piece::B(p) => p.get_valid_moves(board, coord),
@@ -69,7 +140,7 @@ impl Piece {
         }
     }
 
-    pub fn set_coord(&mut self, coord: Square) {
+    pub(crate) fn set_coord(&mut self, coord: Square) {
         match self {
             Piece::P(p) => p.set_coord(coord),
             Piece::B(p) => p.set_coord(coord),
@@ -80,7 +151,7 @@ impl Piece {
         }
     }
 
-    pub fn move_to(&mut self, board: &mut Board, coord_to: Square) -> Result<(), &str> {
+    pub(crate) fn move_to(&mut self, board: &mut Board, coord_to: Square) -> Result<(), MoveError> {
         match self {
             Piece::P(p) => p.move_to(board, coord_to),
             Piece::B(p) => p.move_to(board, coord_to),
@@ -105,4 +176,23 @@ impl Color {
             Color::Black => Color::White,
         }
     }
-}
\ No newline at end of file
+}
+// Sliding pieces stop at the first piece on each line; the squares they see through it are pierced
+fn get_sliding_moves(board: &Board, coord_from: Square, color: Color, attacks: fn(Square, u64) -> u64) -> Vec<(Square, MoveStatus)> {
+    let mut valid_moves = Vec::new();
+
+    let occupancy = board.get_all_occupancy();
+    let own_pieces = board.get_occupancy(color);
+    let direct = attacks(coord_from, occupancy);
+    let pierced = attacks(coord_from, occupancy & !direct) & !direct;
+
+    for position in bitboard::squares(direct & !own_pieces) {
+        valid_moves.push((position, MoveStatus::Capturable { by_color: color, activated: false }));
+    }
+
+    for position in bitboard::squares(pierced & !own_pieces) {
+        valid_moves.push((position, MoveStatus::Pierced { by_color: color, activated: false }));
+    }
+
+    valid_moves
+}
@@ -1,4 +1,4 @@
-use crate::{board::Board, square::{Square, SquareKind}};
+use crate::{board::Board, square::Square, error::MoveError, bitboard};
 
 use super::{Color, MoveStatus};
 
@@ -16,7 +16,7 @@ impl Pawn {
         }
     }
 
-    pub fn set_coord(&mut self, coord: Square) {
+    pub(crate) fn set_coord(&mut self, coord: Square) {
         self.coord = coord;
     }
 
@@ -28,174 +28,78 @@ impl Pawn {
         self.color
     }
 
-    pub fn get_valid_moves(&self, board: &Board, coord_from: Square) -> Vec<(Square, MoveStatus)> {
+    pub(crate) fn get_valid_moves(&self, board: &Board, coord_from: Square) -> Vec<(Square, MoveStatus)> {
         let mut valid_moves = Vec::new();
 
         let current_file = coord_from.get_file();
         let current_rank = coord_from.get_rank();
 
-        // White pawn moves forward by one rank
-        match self.color {
-            Color::Black => {
-                let target_rank = current_rank - 1;
-
-                let is_enemy_piece_left = board.get_piece(Square::from_position((current_file - 1, target_rank))).is_some_and(|x| x.get_color() != self.color );
-                let is_enemy_piece_right = board.get_piece(Square::from_position((current_file + 1, target_rank))).is_some_and(|x| x.get_color() != self.color );
-                let is_enemy_piece_left_en_passant = board.get_piece(Square::from_position((current_file - 1, current_rank))).is_some_and(|x| x.get_color() != self.color );
-                let is_enemy_piece_right_en_passant = board.get_piece(Square::from_position((current_file + 1, current_rank))).is_some_and(|x| x.get_color() != self.color );
-
-                if board.is_empty(Square::from_position((current_file, target_rank))) && board.get_piece(Square::from_position((current_file, target_rank))).is_none() {
-                    valid_moves.push((Square::from_position((current_file, target_rank)), MoveStatus::Movable { by_color: self.color, activated: false }));
-                }
-                // Double move from the starting rank
-                if current_rank == 6 && board.is_empty(Square::from_position((current_file, target_rank))) && board.get_piece(Square::from_position((current_file, target_rank - 1))).is_none() {
-                    valid_moves.push((Square::from_position((current_file, target_rank - 1)), MoveStatus::Movable { by_color: self.color, activated: false }));
-                }
-                // Capture diagonally to the left
-                if current_file > 0 {
-                    let square = Square::from_position((current_file - 1, target_rank));
-                    if is_enemy_piece_left {
-                        valid_moves.push((square, MoveStatus::Capturable { by_color: self.color, activated: false }));
-                    } else {
-                        valid_moves.push((square, MoveStatus::Threaten { by_color: self.color, activated: false }));
-                    }
-                }
-                // Capture diagonally to the right
-                if current_file < 7 {
-                    let square = Square::from_position((current_file + 1, target_rank));
-                    if is_enemy_piece_right{
-                        valid_moves.push((square, MoveStatus::Capturable { by_color: self.color, activated: false }));
-                    } else {
-                        valid_moves.push((square, MoveStatus::Threaten { by_color: self.color, activated: false }));
-                    }
-                }
-
-                if current_rank == 3 && is_enemy_piece_left_en_passant {
-                    if let Some(piece) = board.get_piece(Square::from_position((current_file, current_rank))) {
-                        if let super::Piece::P(_) = piece {
-                            valid_moves.push((Square::from_position((current_file - 1, target_rank)), MoveStatus::EnPassant { by_color: self.color, activated: false }));
-                        }
-                    }
-                }
+        // A pawn on the last rank is waiting to be promoted
+        if !(1..7).contains(&current_rank) {
+            return valid_moves;
+        }
 
-                if current_rank == 3 && is_enemy_piece_right_en_passant {
-                    if let Some(piece) = board.get_piece(Square::from_position((current_file, current_rank))) {
-                        if let super::Piece::P(_) = piece {
-                            valid_moves.push((Square::from_position((current_file + 1, target_rank)), MoveStatus::EnPassant { by_color: self.color, activated: false }));
-                        }
-                    }
-                }
-            },
-            Color::White => {
-                let target_rank = current_rank + 1;
+        let (direction, starting_rank, en_passant_rank) = match self.color {
+            Color::White => (1, 1, 4),
+            Color::Black => (-1, 6, 3),
+        };
+        let target_rank = current_rank + direction;
+        let target = Square::from_position((current_file, target_rank));
 
-                let is_enemy_piece_left = board.get_piece(Square::from_position((current_file - 1, target_rank))).is_some_and(|x| x.get_color() != self.color );
-                let is_enemy_piece_right = board.get_piece(Square::from_position((current_file + 1, target_rank))).is_some_and(|x| x.get_color() != self.color );
-                let is_enemy_piece_left_en_passant = board.get_piece(Square::from_position((current_file - 1, current_rank))).is_some_and(|x| x.get_color() != self.color );
-                let is_enemy_piece_right_en_passant = board.get_piece(Square::from_position((current_file + 1, current_rank))).is_some_and(|x| x.get_color() != self.color );
+        if board.is_empty(target) {
+            valid_moves.push((target, MoveStatus::Movable { by_color: self.color, activated: false }));
 
-                if target_rank <= 7 &&
-                    board.is_empty(Square::from_position((current_file, target_rank))) && 
-                    board.get_piece(Square::from_position((current_file, target_rank))).is_none(){
-                        valid_moves.push((Square::from_position((current_file, target_rank)), MoveStatus::Movable { by_color: self.color, activated: false }));
+            // Double move from the starting rank
+            if current_rank == starting_rank {
+                let double_target = Square::from_position((current_file, target_rank + direction));
 
+                if board.is_empty(double_target) {
+                    valid_moves.push((double_target, MoveStatus::Movable { by_color: self.color, activated: false }));
                 }
-                // Double move from the starting rank
-                if current_rank == 1 && board.is_empty(Square::from_position((current_file, target_rank))) && board.get_piece(Square::from_position((current_file, target_rank + 1))).is_none() {
-                    valid_moves.push((Square::from_position((current_file, target_rank + 1)), MoveStatus::Movable { by_color: self.color, activated: false }));
-                }
-                // Capture diagonally to the left
-                if current_file > 0 && target_rank <= 7 {
-                    let square = Square::from_position((current_file - 1, target_rank));
-                    if is_enemy_piece_left {
-                        valid_moves.push((square, MoveStatus::Capturable { by_color: self.color, activated: false }));
-                    } else {
-                        valid_moves.push((square, MoveStatus::Threaten { by_color: self.color, activated: false }));
-                    }
-                }
-                // Capture diagonally to the right
-                if current_file < 7 && target_rank <= 7 {
-                    let square = Square::from_position((current_file + 1, target_rank));
-                    if is_enemy_piece_right{
-                        valid_moves.push((square, MoveStatus::Capturable { by_color: self.color, activated: false }));
-                    } else {
-                        valid_moves.push((square, MoveStatus::Threaten { by_color: self.color, activated: false }));
-                    }
-                }
+            }
+        }
 
-                if current_rank == 4 && is_enemy_piece_left_en_passant {
-                    if let Some(piece) = board.get_piece(Square::from_position((current_file, current_rank))) {
-                        if let super::Piece::P(_) = piece {
-                            valid_moves.push((Square::from_position((current_file - 1, target_rank)), MoveStatus::EnPassant { by_color: self.color, activated: false }));
-                        }
-                    }
-                }
+        // Capture diagonally
+        for square in bitboard::squares(bitboard::pawn_attacks(coord_from, self.color)) {
+            if board.get_piece(square).is_some_and(|x| x.get_color() != self.color) {
+                valid_moves.push((square, MoveStatus::Capturable { by_color: self.color, activated: false }));
+            } else {
+                valid_moves.push((square, MoveStatus::Threaten { by_color: self.color, activated: false }));
+            }
+        }
 
-                if current_rank == 4 && is_enemy_piece_right_en_passant {
-                    if let Some(piece) = board.get_piece(Square::from_position((current_file, current_rank))) {
-                        if let super::Piece::P(_) = piece {
-                            valid_moves.push((Square::from_position((current_file + 1, target_rank)), MoveStatus::EnPassant { by_color: self.color, activated: false }));
-                        }
-                    }
-                }
+        // Only the pawn that has just made a double move can be taken en passant
+        if let Some(en_passant) = board.get_en_passant() {
+            if current_rank == en_passant_rank && en_passant.get_rank() == target_rank && (en_passant.get_file() - current_file).abs() == 1 {
+                valid_moves.push((en_passant, MoveStatus::EnPassant { by_color: self.color, activated: false }));
             }
         }
 
         valid_moves
     }
 
-    pub fn move_to(&mut self, board: &mut Board, coord_to: Square) -> Result<(), &'static str> {
-        let rank_from = self.coord.get_rank() as usize;
-        let _file_from = self.coord.get_file() as usize;
-        let rank_to = coord_to.get_rank() as usize;
-        let file_to = coord_to.get_file() as usize;
-
-        match self.color {
-            Color::Black => {
-                if rank_from == 1 && rank_to == 0 {
-                    // self.set_state(GameState::Promoting { pawn });
-                }
-
-                let square = board.get_square_mut(coord_to).unwrap();
-                if let SquareKind::Empty(MoveStatus::EnPassant { by_color, .. }) = square {
-                    if *by_color == self.color {
-                        board.despawn(Square::from_position((file_to as i32, rank_from as i32))).ok();
-                        
-                    }
-                }
-            },
-            Color::White => {
-                if rank_from == 6 && rank_to == 7 {
-                    // self.set_state(GameState::Promoting { pawn });
-                } 
-
-                let square = board.get_square_mut(coord_to).unwrap();
-                if let SquareKind::Empty(MoveStatus::EnPassant { by_color, .. }) = square {
-                    if *by_color == self.color {
-                        board.despawn(Square::from_position((file_to as i32, rank_from as i32))).ok();
-                        
-                    }
-                }
-
-            },
+    pub(crate) fn move_to(&mut self, board: &mut Board, coord_to: Square) -> Result<(), MoveError> {
+        // Capturing en passant removes the pawn that passed over the target square
+        if board.get_en_passant() == Some(coord_to) && coord_to.get_file() != self.coord.get_file() {
+            board.despawn(Square::from_position((coord_to.get_file(), self.coord.get_rank())))?;
         }
-        
+
         board.move_piece(self.coord, coord_to)
     }
 
-    pub fn try_into_queen(&self) -> Option<super::Queen> {
+    pub(crate) fn try_into_queen(&self) -> Option<super::Queen> {
         Some(super::Queen::new(self.color))
     }
 
-    pub fn try_into_knight(&self) -> Option<super::Knight> {
+    pub(crate) fn try_into_knight(&self) -> Option<super::Knight> {
         Some(super::Knight::new(self.color))
     }
 
-    pub fn try_into_bishop(&self) -> Option<super::Bishop> {
+    pub(crate) fn try_into_bishop(&self) -> Option<super::Bishop> {
         Some(super::Bishop::new(self.color))
     }
 
-    pub fn try_into_rook(&self) -> Option<super::Rook> {
+    pub(crate) fn try_into_rook(&self) -> Option<super::Rook> {
         Some(super::Rook::new(self.color))
     }
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use crate::{board::Board, square::Square};
+use crate::{board::Board, square::Square, error::MoveError, bitboard};
 
 use super::{Color, MoveStatus};
 
@@ -16,7 +16,7 @@ impl Queen {
         }
     }
 
-    pub fn set_coord(&mut self, coord: Square) {
+    pub(crate) fn set_coord(&mut self, coord: Square) {
         self.coord = coord;
     }
 
@@ -28,94 +28,11 @@ impl Queen {
         self.color
     }
 
-    pub fn get_valid_moves(&self, board: &mut Board, coord_from: Square) -> Vec<(Square, MoveStatus)> {
-        let mut valid_moves = Vec::new();
-
-        let current_file = coord_from.get_file();
-        let current_rank = coord_from.get_rank();
-
-        let mut a = 0;
-        let mut b = 0;
-        let mut c = 0;
-        let mut d = 0;
-        let mut e = 0;
-        let mut f = 0;
-        let mut g = 0;
-        let mut h = 0;
-        let mut is_king_pierced = false;
-
-        let mut lay = |file, rank, pierce_counter: &mut u32| {
-            let position = Square::from_position((rank, file));
-            let mut capture_status = if *pierce_counter > 0 {
-                MoveStatus::Pierced { by_color: self.color, activated: false }
-            } else {
-                MoveStatus::Capturable { by_color: self.color, activated: false }
-            };
-
-            if !board.is_empty(position) {
-                let query = board.get_piece_mut(position).unwrap();
-                let color = query.get_color();
-                
-                if color != self.color {
-                    if let super::Piece::K(ref mut king) = *query {
-                        king.set_checked(true);
-                        is_king_pierced = true;
-                    }
-                    valid_moves.push((position, capture_status));
-                }
-
-                *pierce_counter += 1;
-            } else {
-                if *pierce_counter > 0 && !is_king_pierced {
-                    capture_status = MoveStatus::None;
-                }
-
-                if *pierce_counter < 2 {
-                    valid_moves.push((position, capture_status));
-                }
-            }
-        };
-
-        for i in 1..=i32::min(current_file, 7 - current_rank) {
-            lay(current_file - i, current_rank + i, &mut a);
-        }
-
-        for i in 1..=i32::min(7 - current_file, 7 - current_rank) {
-            lay(current_file + i, current_rank + i, &mut b);
-        }
-
-        for i in 1..=i32::min(7 - current_file, current_rank) {
-            lay(current_file + i, current_rank - i, &mut c);
-        }
-
-        for i in 1..=i32::min(current_file, current_rank) {
-            lay(current_file - i, current_rank - i, &mut d);
-        }
-
-        for file in (current_file + 1)..8 {
-            lay(file, current_rank, &mut e);
-        }
-    
-        // Horizontal moves to the left
-        for file in (0..current_file).rev() {
-            lay(file, current_rank, &mut f);
-        }
-    
-        // Vertical moves upwards
-        for rank in (current_rank + 1)..8 {
-            lay(current_file, rank, &mut g);
-        }
-    
-        // Vertical moves downwards
-        for rank in (0..current_rank).rev() {
-            lay(current_file, rank, &mut h);
-        }
-
-
-        valid_moves
+    pub(crate) fn get_valid_moves(&self, board: &Board, coord_from: Square) -> Vec<(Square, MoveStatus)> {
+        super::get_sliding_moves(board, coord_from, self.color, bitboard::queen_attacks)
     }
 
-    pub fn move_to(&mut self, board: &mut Board, coord_to: Square) -> Result<(), &'static str> {
+    pub(crate) fn move_to(&mut self, board: &mut Board, coord_to: Square) -> Result<(), MoveError> {
         board.move_piece(self.coord, coord_to)
     }
     
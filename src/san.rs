@@ -0,0 +1,228 @@
+use crate::{board::Board, square::Square, pieces::{Piece, PieceKind}, moves::Move, error::MoveError};
+
+impl Board {
+    // Standard Algebraic Notation of a legal move in this position, such as "Nbd7", "exd6" or "e8=Q+"
+    pub fn get_san(&mut self, mv: Move) -> Result<String, MoveError> {
+        let legal_moves = self.get_all_playable_moves();
+
+        if !legal_moves.contains(&mv) {
+            return Err(MoveError::IllegalMove);
+        }
+
+        let piece = self.get_piece(mv.get_from()).ok_or(MoveError::NoPieceOnSquare(mv.get_from()))?.clone();
+        let coord_from = mv.get_from();
+        let coord_to = mv.get_to();
+        let mut san = String::new();
+
+        match piece {
+            Piece::K(_) if (coord_to.get_file() - coord_from.get_file()).abs() == 2 => {
+                san.push_str(if coord_to.get_file() > coord_from.get_file() { "O-O" } else { "O-O-O" });
+            },
+            Piece::P(_) => {
+                let is_capture = coord_from.get_file() != coord_to.get_file();
+
+                if is_capture {
+                    san.push(get_file_char(coord_from));
+                    san.push('x');
+                }
+
+                san.push_str(&coord_to.to_string());
+
+                if let Some(kind) = mv.get_promotion() {
+                    san.push('=');
+                    san.push(kind.to_char().to_ascii_uppercase());
+                }
+            },
+            _ => {
+                san.push(piece.get_kind().to_char().to_ascii_uppercase());
+
+                // Name the origin only as far as needed to tell the piece apart from its twins
+                let twins = legal_moves
+                    .iter()
+                    .filter(|x| x.get_to() == coord_to && x.get_from() != coord_from)
+                    .filter(|x| self.get_piece(x.get_from()).is_some_and(|p| p.get_kind() == piece.get_kind()))
+                    .map(|x| x.get_from())
+                    .collect::<Vec<Square>>();
+
+                if !twins.is_empty() {
+                    if twins.iter().all(|x| x.get_file() != coord_from.get_file()) {
+                        san.push(get_file_char(coord_from));
+                    } else if twins.iter().all(|x| x.get_rank() != coord_from.get_rank()) {
+                        san.push(get_rank_char(coord_from));
+                    } else {
+                        san.push_str(&coord_from.to_string());
+                    }
+                }
+
+                if !self.is_empty(coord_to) {
+                    san.push('x');
+                }
+
+                san.push_str(&coord_to.to_string());
+            },
+        }
+
+        let undo = self.make_move(mv)?;
+        let turn = self.get_turn();
+
+        if self.is_in_check(turn) {
+            san.push(if self.get_all_playable_moves().is_empty() { '#' } else { '+' });
+        }
+
+        self.unmake_move(&undo)?;
+
+        Ok(san)
+    }
+
+    // Finds the legal move a SAN string names, accepting check marks and annotations such as "!?"
+    pub fn parse_san(&self, san: &str) -> Result<Move, MoveError> {
+        let san = san
+            .trim_end_matches(['+', '#', '!', '?'])
+            .trim_end_matches("e.p.")
+            .trim_end();
+        let legal_moves = self.get_all_playable_moves();
+
+        if let Some(side) = match san {
+            "O-O" | "0-0" => Some(1),
+            "O-O-O" | "0-0-0" => Some(-1),
+            _ => None,
+        } {
+            return legal_moves
+                .into_iter()
+                .find(|x| {
+                    matches!(self.get_piece(x.get_from()), Some(Piece::K(_))) &&
+                        x.get_to().get_file() - x.get_from().get_file() == side * 2
+                })
+                .ok_or(MoveError::IllegalMove);
+        }
+
+        if !san.is_ascii() || san.len() < 2 {
+            return Err(MoveError::InvalidNotation);
+        }
+
+        let (kind, rest) = match san.chars().next() {
+            Some(c @ ('N' | 'B' | 'R' | 'Q' | 'K')) => (PieceKind::from_char(c).ok_or(MoveError::InvalidNotation)?, &san[1..]),
+            _ => (PieceKind::Pawn, san),
+        };
+
+        // The promotion piece follows the target square, usually after an '='
+        let (rest, promotion) = match (rest.split_once('='), rest.char_indices().last()) {
+            (Some((rest, piece)), _) => (rest, Some(piece.chars().next().and_then(PieceKind::from_char).ok_or(MoveError::InvalidNotation)?)),
+            (None, Some((i, c))) if c.is_ascii_uppercase() => (&rest[..i], Some(PieceKind::from_char(c).ok_or(MoveError::InvalidNotation)?)),
+            _ => (rest, None),
+        };
+
+        if rest.len() < 2 {
+            return Err(MoveError::InvalidNotation);
+        }
+
+        let coord_to = rest[rest.len() - 2..].parse::<Square>()?;
+        let mut from_file = None;
+        let mut from_rank = None;
+
+        for c in rest[..rest.len() - 2].chars() {
+            match c {
+                'a'..='h' => from_file = Some(c as i32 - 'a' as i32),
+                '1'..='8' => from_rank = Some(c as i32 - '1' as i32),
+                'x' | ':' | '-' => (),
+                _ => return Err(MoveError::InvalidNotation),
+            }
+        }
+
+        let candidates = legal_moves
+            .into_iter()
+            .filter(|x| x.get_to() == coord_to)
+            .filter(|x| self.get_piece(x.get_from()).is_some_and(|p| p.get_kind() == kind))
+            // A pawn changes file only by capturing, and a capture always names the file it came from
+            .filter(|x| match from_file {
+                Some(file) => x.get_from().get_file() == file,
+                None => kind != PieceKind::Pawn || x.get_from().get_file() == x.get_to().get_file(),
+            })
+            .filter(|x| from_rank.is_none_or(|rank| x.get_from().get_rank() == rank))
+            .collect::<Vec<Move>>();
+        let is_promotion = candidates.iter().any(|x| x.get_promotion().is_some());
+        let candidates = candidates
+            .into_iter()
+            .filter(|x| x.get_promotion() == promotion)
+            .collect::<Vec<Move>>();
+
+        match candidates[..] {
+            [mv] => Ok(mv),
+            [] if is_promotion && promotion.is_none() => Err(MoveError::PromotionRequired),
+            [] => Err(MoveError::IllegalMove),
+            _ => Err(MoveError::AmbiguousMove),
+        }
+    }
+}
+
+fn get_file_char(square: Square) -> char {
+    (b'a' + square.get_file() as u8) as char
+}
+
+fn get_rank_char(square: Square) -> char {
+    (b'1' + square.get_rank() as u8) as char
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{board::Board, moves::Move, error::MoveError};
+
+    fn assert_san(fen: &str, uci: &str, san: &str) {
+        let mut board = Board::from_fen(fen).unwrap();
+        let mv = Move::from_uci(uci).unwrap();
+
+        assert_eq!(board.get_san(mv).unwrap(), san);
+        assert_eq!(board.parse_san(san).unwrap(), mv);
+    }
+
+    #[test]
+    fn piece_and_pawn_moves() {
+        assert_san("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", "g1f3", "Nf3");
+        assert_san("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2", "e4d5", "exd5");
+        assert_san("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3", "e5f6", "exf6");
+    }
+
+    #[test]
+    fn castling() {
+        assert_san("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "e1g1", "O-O");
+        assert_san("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1", "e8c8", "O-O-O");
+    }
+
+    #[test]
+    fn promotion_with_check_and_mate() {
+        assert_san("8/4P3/8/8/8/8/k7/7K w - - 0 1", "e7e8q", "e8=Q");
+        assert_san("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7b8q", "b8=Q+");
+        assert_san("k7/4P3/1K6/8/8/8/8/8 w - - 0 1", "e7e8r", "e8=R#");
+    }
+
+    #[test]
+    fn disambiguation() {
+        assert_san("4k3/8/8/8/8/8/8/R4RK1 w - - 0 1", "a1d1", "Rad1");
+        assert_san("R7/8/8/8/8/8/8/R3K2k w - - 0 1", "a1a4", "R1a4");
+        assert_san("3k4/8/8/8/8/2Q1Q3/8/4Q1K1 w - - 0 1", "e3d2", "Qe3d2#");
+    }
+
+    #[test]
+    fn rejects_illegal_and_ambiguous_input() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/R4RK1 w - - 0 1").unwrap();
+
+        assert_eq!(board.parse_san("Rd1"), Err(MoveError::AmbiguousMove));
+        assert_eq!(board.parse_san("Nf3"), Err(MoveError::IllegalMove));
+        assert_eq!(board.parse_san("Z9"), Err(MoveError::InvalidNotation));
+
+        let board = Board::from_fen("8/4P3/8/8/8/8/k7/7K w - - 0 1").unwrap();
+
+        assert_eq!(board.parse_san("e8"), Err(MoveError::PromotionRequired));
+
+        // Pawn captures need the file they start from
+        let board = Board::from_fen("4k3/8/8/3p4/2P1P3/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.parse_san("d5"), Err(MoveError::IllegalMove));
+        assert_eq!(board.parse_san("cxd5"), Ok(Move::from_uci("c4d5").unwrap()));
+
+        let board = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+
+        assert_eq!(board.parse_san("d6"), Err(MoveError::IllegalMove));
+        assert_eq!(board.parse_san("exd6"), Ok(Move::from_uci("e5d6").unwrap()));
+    }
+}
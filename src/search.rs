@@ -0,0 +1,349 @@
+use std::{sync::{Arc, atomic::{AtomicBool, Ordering}}, time::{Duration, Instant}};
+
+use crate::{board::Board, game::GameManager, pieces::PieceKind, moves::Move, evaluation::{self, get_piece_value}, transposition::{Bound, TranspositionTable, DEFAULT_HASH_SIZE}};
+
+pub const MATE: i32 = 30000;
+pub const MAX_DEPTH: u32 = 64;
+
+const INFINITY: i32 = MATE + 1;
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct SearchLimits {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub time: Option<Duration>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    pub best_move: Option<Move>,
+    // Centipawns from the point of view of the side to move
+    pub score: i32,
+    pub depth: u32,
+    pub nodes: u64,
+    pub time: Duration,
+    pub pv: Vec<Move>,
+}
+
+impl SearchResult {
+    // Full moves until mate, negative when the side to move is getting mated
+    pub fn get_mate_in(&self) -> Option<i32> {
+        if self.score.abs() < MATE - MAX_DEPTH as i32 {
+            return None;
+        }
+
+        let moves = (MATE - self.score.abs() + 1) / 2;
+
+        Some(if self.score > 0 { moves } else { -moves })
+    }
+}
+
+pub struct Search {
+    limits: SearchLimits,
+    stop: Arc<AtomicBool>,
+    start: Instant,
+    nodes: u64,
+    is_stopped: bool,
+    table: TranspositionTable,
+    // Hashes of the positions before the current one, from the game and then from the search
+    positions: Vec<u64>,
+}
+
+impl Search {
+    pub fn new(limits: SearchLimits) -> Self {
+        Self::with_table(limits, TranspositionTable::new(DEFAULT_HASH_SIZE))
+    }
+
+    // Searches with a table kept from earlier searches, so their results carry over
+    pub fn with_table(limits: SearchLimits, table: TranspositionTable) -> Self {
+        Self {
+            limits,
+            stop: Arc::new(AtomicBool::new(false)),
+            start: Instant::now(),
+            nodes: 0,
+            is_stopped: false,
+            table,
+            positions: Vec::new(),
+        }
+    }
+
+    pub fn into_table(self) -> TranspositionTable {
+        self.table
+    }
+
+    // Setting the flag from another thread ends the search as soon as possible
+    pub fn get_stop_flag(&self) -> Arc<AtomicBool> {
+        self.stop.clone()
+    }
+
+    pub fn run(&mut self, game: &GameManager, mut report: impl FnMut(&SearchResult)) -> SearchResult {
+        self.start = Instant::now();
+        self.nodes = 0;
+        self.is_stopped = false;
+        self.table.new_search();
+        self.positions = game.get_positions().to_vec();
+
+        let legal_moves = game.get_legal_moves();
+        let mut board = game.get_board().clone();
+        let mut result = SearchResult {
+            best_move: legal_moves.first().copied(),
+            score: 0,
+            depth: 0,
+            nodes: 0,
+            time: Duration::ZERO,
+            pv: Vec::new(),
+        };
+
+        if legal_moves.is_empty() {
+            return result;
+        }
+
+        let max_depth = self.limits.depth.unwrap_or(MAX_DEPTH).clamp(1, MAX_DEPTH);
+
+        for depth in 1..=max_depth {
+            let mut pv = Vec::new();
+            let score = self.negamax(&mut board, depth, 0, -INFINITY, INFINITY, &result.pv, &mut pv);
+
+            // An unfinished iteration is thrown away
+            if self.is_stopped {
+                break;
+            }
+
+            result = SearchResult {
+                best_move: pv.first().copied(),
+                score,
+                depth,
+                nodes: self.nodes,
+                time: self.start.elapsed(),
+                pv,
+            };
+            report(&result);
+
+            // A deeper search would not find a shorter mate, and likely not finish in time
+            if result.get_mate_in().is_some() || self.limits.time.is_some_and(|x| self.start.elapsed() * 2 > x) {
+                break;
+            }
+        }
+
+        result.nodes = self.nodes;
+        result.time = self.start.elapsed();
+
+        result
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn negamax(&mut self, board: &mut Board, depth: u32, ply: u32, mut alpha: i32, beta: i32, pv_line: &[Move], pv: &mut Vec<Move>) -> i32 {
+        if depth == 0 {
+            return self.quiescence(board, alpha, beta);
+        }
+
+        self.nodes += 1;
+
+        if self.should_stop() {
+            return 0;
+        }
+
+        let hash = board.get_hash();
+
+        // Coming back to an earlier position means the other side can go on repeating it
+        if ply > 0 && self.is_repetition(board) {
+            return 0;
+        }
+
+        let entry = self.table.probe(hash);
+
+        // The root always searches, so the principal variation and best move come out whole
+        if let Some(entry) = entry.filter(|x| ply > 0 && x.get_depth() >= depth) {
+            let score = entry.get_score(ply);
+
+            match entry.get_bound() {
+                Bound::Exact => {
+                    pv.extend(self.get_table_line(board, depth));
+                    return score;
+                },
+                Bound::Lower if score >= beta => return score,
+                Bound::Upper if score <= alpha => return score,
+                _ => (),
+            }
+        }
+
+        // The previous principal variation leads, then the best move this position had before
+        let hint = pv_line.first().copied().or(entry.and_then(|x| x.get_move()));
+        let moves = board.get_all_playable_moves();
+        let moves = order_moves(board, moves, hint.as_ref());
+        let alpha_start = alpha;
+        let mut best_move = None;
+
+        if moves.is_empty() {
+            return match board.is_in_check(board.get_turn()) {
+                true => -MATE + ply as i32,
+                false => 0,
+            };
+        }
+
+        // Checkmate on the move that completes the fifty still counts, so this comes after the mate check
+        if ply > 0 && board.get_halfmove_clock() >= 100 {
+            return 0;
+        }
+
+        for mv in moves {
+            let mut child_pv = Vec::new();
+
+            // Only the first move keeps following the previous principal variation
+            let child_pv_line = match pv_line.first() == Some(&mv) {
+                true => &pv_line[1..],
+                false => &[],
+            };
+
+            self.positions.push(hash);
+
+            let undo = board.make_move(mv).expect("legal moves can always be played");
+            let score = -self.negamax(board, depth - 1, ply + 1, -beta, -alpha, child_pv_line, &mut child_pv);
+
+            board.unmake_move(&undo).expect("a move just made can be taken back");
+            self.positions.pop();
+
+            if self.is_stopped {
+                return 0;
+            }
+
+            if score > alpha {
+                alpha = score;
+                best_move = Some(mv);
+
+                pv.clear();
+                pv.push(mv);
+                pv.extend(child_pv);
+
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+
+        let bound = match alpha {
+            alpha if alpha >= beta => Bound::Lower,
+            alpha if alpha > alpha_start => Bound::Exact,
+            _ => Bound::Upper,
+        };
+
+        self.table.store(hash, depth, bound, alpha, best_move, ply);
+
+        alpha
+    }
+
+    // Resolves captures and promotions so the evaluation never stops in the middle of an exchange
+    fn quiescence(&mut self, board: &mut Board, mut alpha: i32, beta: i32) -> i32 {
+        self.nodes += 1;
+
+        if self.should_stop() {
+            return 0;
+        }
+
+        let stand_pat = evaluation::evaluate(board).get_score(board.get_turn());
+
+        if stand_pat >= beta {
+            return beta;
+        }
+
+        alpha = alpha.max(stand_pat);
+
+        let moves = board.get_all_playable_moves()
+            .into_iter()
+            .filter(|mv| is_capture(board, mv) || mv.get_promotion().is_some())
+            .collect::<Vec<Move>>();
+
+        for mv in order_moves(board, moves, None) {
+            let undo = board.make_move(mv).expect("legal moves can always be played");
+            let score = -self.quiescence(board, -beta, -alpha);
+
+            board.unmake_move(&undo).expect("a move just made can be taken back");
+
+            if self.is_stopped {
+                return 0;
+            }
+
+            if score >= beta {
+                return beta;
+            }
+
+            alpha = alpha.max(score);
+        }
+
+        alpha
+    }
+
+    // Follows the best moves stored in the table, to finish a principal variation that a table hit cut short
+    fn get_table_line(&self, board: &mut Board, depth: u32) -> Vec<Move> {
+        let mut line = Vec::new();
+        let mut undos = Vec::new();
+
+        while line.len() < depth as usize {
+            let Some(mv) = self.table.probe(board.get_hash()).and_then(|x| x.get_move()) else {
+                break;
+            };
+
+            // Two positions sharing a hash could hand over a move that is not legal here
+            if !board.get_all_playable_moves().contains(&mv) {
+                break;
+            }
+
+            undos.push(board.make_move(mv).expect("legal moves can always be played"));
+            line.push(mv);
+        }
+
+        for undo in undos.iter().rev() {
+            board.unmake_move(undo).expect("a move just made can be taken back");
+        }
+
+        line
+    }
+
+    fn is_repetition(&self, board: &Board) -> bool {
+        let hash = board.get_hash();
+        // Nothing before the last capture or pawn move can come back
+        let reversible = (board.get_halfmove_clock() as usize).min(self.positions.len());
+
+        self.positions[self.positions.len() - reversible..].contains(&hash)
+    }
+
+    fn should_stop(&mut self) -> bool {
+        if !self.is_stopped {
+            self.is_stopped = self.stop.load(Ordering::Relaxed) ||
+                self.limits.nodes.is_some_and(|x| self.nodes >= x) ||
+                (self.nodes.is_multiple_of(1024) && self.limits.time.is_some_and(|x| self.start.elapsed() >= x));
+        }
+
+        self.is_stopped
+    }
+}
+
+fn is_capture(board: &Board, mv: &Move) -> bool {
+    !board.is_empty(mv.get_to()) ||
+        board.get_en_passant() == Some(mv.get_to()) && board.get_piece(mv.get_from()).is_some_and(|x| x.get_kind() == PieceKind::Pawn)
+}
+
+// The principal variation move first, then captures of the most valuable victim by the least valuable attacker
+fn order_moves(board: &Board, mut moves: Vec<Move>, pv_move: Option<&Move>) -> Vec<Move> {
+    moves.sort_by_cached_key(|mv| {
+        if pv_move == Some(mv) {
+            return i32::MIN;
+        }
+
+        let victim = match board.get_piece(mv.get_to()) {
+            Some(piece) => get_piece_value(piece.get_kind()),
+            // Only an en passant capture takes on an empty square
+            None if is_capture(board, mv) => get_piece_value(PieceKind::Pawn),
+            None => 0,
+        };
+        let attacker = board.get_piece(mv.get_from()).map_or(0, |x| get_piece_value(x.get_kind()));
+        let promotion = mv.get_promotion().map_or(0, get_piece_value);
+
+        match is_capture(board, mv) || promotion > 0 {
+            true => -(victim * 10 - attacker + promotion) - 1,
+            false => 0,
+        }
+    });
+
+    moves
+}
@@ -1,4 +1,6 @@
-use crate::pieces::{Piece, MoveStatus};
+use std::str::FromStr;
+
+use crate::{pieces::{Piece, MoveStatus}, error::MoveError};
 
 #[allow(dead_code)]
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -16,33 +18,19 @@ pub enum Square {
 
 impl Square {
     pub fn from_position(pos: (i32, i32)) -> Square {
-        if pos.0 > 7 || pos.1 > 7 {
+        if !(0..8).contains(&pos.0) || !(0..8).contains(&pos.1) {
             panic!("Invalid position");
         }
 
         let calc = pos.0 * 8 + pos.1;
 
-        unsafe { std::mem::transmute(calc as u8) }
+        unsafe { std::mem::transmute::<u8, Square>(calc as u8) }
     }
 
-    pub fn from_str(s: &str) -> Option<Square> {
-        let mut chars = s.chars();
-
-        let file = chars.next().unwrap().to_ascii_uppercase() as u8;
-        let rank = chars.next().unwrap().to_digit(10);
-        rank?;
-        
-        let rank = rank.unwrap() as u8;
-
-        if !(b'A'..=b'H').contains(&file) || !(1..=8).contains(&rank) {
-            return None;
-        }
-
-        let calc = (file - b'A') * 8 + (rank - 1);
-
-        Some(unsafe { std::mem::transmute(calc) })
+    pub fn from_index(index: usize) -> Square {
+        Square::from_position(((index / 8) as i32, (index % 8) as i32))
     }
-    
+
     pub fn get_rank(self) -> i32 {
         let calc = self as u8;
 
@@ -56,8 +44,37 @@ impl Square {
     }
 }
 
+impl FromStr for Square {
+    type Err = MoveError;
+
+    fn from_str(s: &str) -> Result<Square, MoveError> {
+        let mut chars = s.chars();
+
+        let file = chars.next().filter(char::is_ascii).ok_or(MoveError::InvalidNotation)?.to_ascii_uppercase() as u8;
+        let rank = chars.next().and_then(|x| x.to_digit(10)).ok_or(MoveError::InvalidNotation)? as u8;
+
+        if chars.next().is_some() || !(b'A'..=b'H').contains(&file) || !(1..=8).contains(&rank) {
+            return Err(MoveError::InvalidNotation);
+        }
+
+        let calc = (file - b'A') * 8 + (rank - 1);
+
+        Ok(unsafe { std::mem::transmute::<u8, Square>(calc) })
+    }
+}
+
+impl std::fmt::Display for Square {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if *self == Square::None {
+            return write!(f, "-");
+        }
+
+        write!(f, "{}{}", (b'a' + self.get_file() as u8) as char, self.get_rank() + 1)
+    }
+}
+
 #[derive(Clone)]
-pub enum SquareKind {
+pub(crate) enum SquareKind {
     Empty(MoveStatus),
     Occupied(Piece, MoveStatus),
 }
@@ -0,0 +1,179 @@
+use crate::{moves::Move, search::{MATE, MAX_DEPTH}};
+
+pub const DEFAULT_HASH_SIZE: usize = 16;
+pub const MAX_HASH_SIZE: usize = 1024;
+
+// How the stored score relates to the true score of the position
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Bound {
+    Exact,
+    // The search failed high, so the true score is at least this
+    Lower,
+    // The search failed low, so the true score is at most this
+    Upper,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Entry {
+    hash: u64,
+    depth: u32,
+    bound: Bound,
+    score: i32,
+    best_move: Option<Move>,
+    age: u8,
+}
+
+impl Entry {
+    pub fn get_depth(&self) -> u32 {
+        self.depth
+    }
+
+    pub fn get_bound(&self) -> Bound {
+        self.bound
+    }
+
+    // Mate scores are stored as distances from the stored position, and read back as distances from the root
+    pub fn get_score(&self, ply: u32) -> i32 {
+        match self.score {
+            score if score >= MATE - MAX_DEPTH as i32 => score - ply as i32,
+            score if score <= -MATE + MAX_DEPTH as i32 => score + ply as i32,
+            score => score,
+        }
+    }
+
+    pub fn get_move(&self) -> Option<Move> {
+        self.best_move
+    }
+}
+
+// Remembers what earlier searches found about each position, by Zobrist hash
+#[derive(Clone, Default)]
+pub struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+    // Goes up with every search, so entries of older searches give way first
+    age: u8,
+}
+
+impl TranspositionTable {
+    // Size in megabytes
+    pub fn new(size: usize) -> Self {
+        let mut table = Self::default();
+        table.resize(size);
+
+        table
+    }
+
+    pub fn resize(&mut self, size: usize) {
+        let count = size.clamp(1, MAX_HASH_SIZE) * 1024 * 1024 / std::mem::size_of::<Option<Entry>>();
+
+        self.entries = vec![None; count];
+        self.age = 0;
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+        self.age = 0;
+    }
+
+    pub(crate) fn new_search(&mut self) {
+        self.age = self.age.wrapping_add(1);
+    }
+
+    pub(crate) fn probe(&self, hash: u64) -> Option<Entry> {
+        self.entries
+            .get(self.get_index(hash))
+            .copied()
+            .flatten()
+            .filter(|x| x.hash == hash)
+    }
+
+    pub(crate) fn store(&mut self, hash: u64, depth: u32, bound: Bound, score: i32, best_move: Option<Move>, ply: u32) {
+        let index = self.get_index(hash);
+        let Some(slot) = self.entries.get_mut(index) else {
+            return;
+        };
+
+        // Deeper results of the current search stay; anything else gives way
+        if let Some(entry) = slot {
+            if entry.hash != hash && entry.age == self.age && entry.depth > depth {
+                return;
+            }
+        }
+
+        let best_move = match slot {
+            // A search that failed low finds no best move, so keep the one found before
+            Some(entry) if best_move.is_none() && entry.hash == hash => entry.best_move,
+            _ => best_move,
+        };
+        let score = match score {
+            score if score >= MATE - MAX_DEPTH as i32 => score + ply as i32,
+            score if score <= -MATE + MAX_DEPTH as i32 => score - ply as i32,
+            score => score,
+        };
+
+        *slot = Some(Entry { hash, depth, bound, score, best_move, age: self.age });
+    }
+
+    fn get_index(&self, hash: u64) -> usize {
+        (hash % self.entries.len().max(1) as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bound, TranspositionTable};
+    use crate::{moves::Move, search::MATE};
+
+    #[test]
+    fn stores_and_probes_by_hash() {
+        let mut table = TranspositionTable::new(1);
+        let mv = Move::from_uci("e2e4").unwrap();
+
+        table.store(0x1234, 5, Bound::Lower, 42, Some(mv), 3);
+
+        let entry = table.probe(0x1234).unwrap();
+
+        assert_eq!(entry.get_depth(), 5);
+        assert_eq!(entry.get_bound(), Bound::Lower);
+        assert_eq!(entry.get_score(0), 42);
+        assert_eq!(entry.get_move(), Some(mv));
+        assert!(table.probe(0x1235).is_none());
+
+        table.clear();
+
+        assert!(table.probe(0x1234).is_none());
+    }
+
+    #[test]
+    fn mate_scores_follow_the_ply() {
+        let mut table = TranspositionTable::new(1);
+
+        // Mate in 3 plies from a position 4 plies deep is mate in 5 plies from a root 2 plies deep
+        table.store(7, 3, Bound::Exact, MATE - 7, None, 4);
+
+        assert_eq!(table.probe(7).unwrap().get_score(2), MATE - 5);
+
+        table.store(7, 3, Bound::Exact, -MATE + 7, None, 4);
+
+        assert_eq!(table.probe(7).unwrap().get_score(2), -MATE + 5);
+    }
+
+    #[test]
+    fn deeper_entries_of_the_current_search_are_kept() {
+        let mut table = TranspositionTable::new(1);
+        let size = table.entries.len() as u64;
+
+        table.store(1, 6, Bound::Exact, 10, None, 0);
+        table.store(1 + size, 2, Bound::Exact, 20, None, 0);
+
+        assert!(table.probe(1).is_some());
+        assert!(table.probe(1 + size).is_none());
+
+        // Entries of an older search give way to anything
+        table.new_search();
+        table.store(1 + size, 2, Bound::Exact, 20, None, 0);
+
+        assert!(table.probe(1).is_none());
+        assert_eq!(table.probe(1 + size).unwrap().get_score(0), 20);
+    }
+}
@@ -0,0 +1,247 @@
+use std::{error::Error, io::{self, BufRead}, sync::{Arc, atomic::{AtomicBool, Ordering}}, thread::{self, JoinHandle}, time::Duration, mem};
+
+use crate::{game::GameManager, pieces::Color, moves::Move, error::MoveError, search::{Search, SearchLimits, SearchResult}, transposition::{TranspositionTable, DEFAULT_HASH_SIZE, MAX_HASH_SIZE}};
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct GoLimits {
+    pub depth: Option<u32>,
+    pub movetime: Option<u64>,
+    pub wtime: Option<u64>,
+    pub btime: Option<u64>,
+    pub winc: Option<u64>,
+    pub binc: Option<u64>,
+    pub nodes: Option<u64>,
+    pub infinite: bool,
+}
+
+impl GoLimits {
+    pub fn from_args(args: &[&str]) -> Self {
+        let mut limits = GoLimits::default();
+        let mut tokens = args.iter();
+
+        while let Some(&token) = tokens.next() {
+            let mut value = || tokens.next().and_then(|x| x.parse::<u64>().ok());
+
+            match token {
+                "depth" => limits.depth = value().map(|x| x as u32),
+                "movetime" => limits.movetime = value(),
+                "wtime" => limits.wtime = value(),
+                "btime" => limits.btime = value(),
+                "winc" => limits.winc = value(),
+                "binc" => limits.binc = value(),
+                "nodes" => limits.nodes = value(),
+                "infinite" => limits.infinite = true,
+                _ => (),
+            }
+        }
+
+        limits
+    }
+}
+
+fn format_info(result: &SearchResult) -> String {
+    let score = match result.get_mate_in() {
+        Some(moves) => format!("mate {}", moves),
+        None => format!("cp {}", result.score),
+    };
+    let millis = result.time.as_millis() as u64;
+    let pv = result.pv
+        .iter()
+        .map(|mv| mv.to_string())
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    format!(
+        "info depth {} score {} nodes {} time {} nps {} pv {}",
+        result.depth, score, result.nodes, millis, result.nodes * 1000 / millis.max(1), pv
+    )
+}
+
+pub struct Uci {
+    // None after a `position` command failed, so a following `go` cannot search a stale position
+    game: Option<GameManager>,
+    stop: Arc<AtomicBool>,
+    // Kept between searches; the search thread holds it while it runs and hands it back when done
+    table: TranspositionTable,
+    // Megabytes set with the Hash option, to rebuild the table at if it is lost
+    hash_size: usize,
+    search: Option<JoinHandle<TranspositionTable>>,
+}
+
+impl Uci {
+    pub fn new() -> Self {
+        Self {
+            game: Some(GameManager::standard()),
+            stop: Arc::new(AtomicBool::new(false)),
+            table: TranspositionTable::new(DEFAULT_HASH_SIZE),
+            hash_size: DEFAULT_HASH_SIZE,
+            search: None,
+        }
+    }
+
+    pub fn run(&mut self) {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+
+            if !self.handle(&line) {
+                return;
+            }
+        }
+
+        self.stop_search();
+    }
+
+    // Returns false once the GUI asks to quit
+    pub fn handle(&mut self, line: &str) -> bool {
+        let tokens = line.split_whitespace().collect::<Vec<&str>>();
+
+        match tokens.first() {
+            Some(&"uci") => {
+                println!("id name {} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+                println!("id author loldruger");
+                println!("option name Hash type spin default {} min 1 max {}", DEFAULT_HASH_SIZE, MAX_HASH_SIZE);
+                println!("option name Clear Hash type button");
+                println!("uciok");
+            },
+            Some(&"isready") => println!("readyok"),
+            Some(&"setoption") => self.set_option(&tokens[1..]),
+            Some(&"ucinewgame") => {
+                self.stop_search();
+                self.game = Some(GameManager::standard());
+                self.table.clear();
+            },
+            Some(&"position") => {
+                self.game = match self.get_position(&tokens[1..]) {
+                    Ok(game) => Some(game),
+                    Err(e) => {
+                        println!("info string {}", e);
+                        None
+                    },
+                };
+            },
+            Some(&"go") => self.go(GoLimits::from_args(&tokens[1..])),
+            Some(&"stop") => self.stop_search(),
+            // Not part of the protocol, but handy to see why the engine likes a position
+            Some(&"eval") => match &self.game {
+                Some(game) => print!("{}", game.evaluate()),
+                None => println!("info string no position set"),
+            },
+            Some(&"quit") => {
+                self.stop_search();
+
+                return false;
+            },
+            // Unknown commands are ignored, as the protocol asks
+            _ => (),
+        }
+
+        true
+    }
+
+    fn set_option(&mut self, args: &[&str]) {
+        let value_at = args.iter().position(|&x| x == "value").unwrap_or(args.len());
+        let name = args.get(1..value_at).unwrap_or_default().join(" ");
+        let value = args.get(value_at + 1..).unwrap_or_default().join(" ");
+
+        // The table cannot change under a running search
+        self.stop_search();
+
+        match name.to_lowercase().as_str() {
+            "hash" => match value.parse::<usize>() {
+                Ok(size) => {
+                    self.hash_size = size;
+                    self.table.resize(size);
+                },
+                Err(_) => println!("info string invalid Hash value '{}'", value),
+            },
+            "clear hash" => self.table.clear(),
+            _ => println!("info string unknown option '{}'", name),
+        }
+    }
+
+    fn get_position(&self, args: &[&str]) -> Result<GameManager, Box<dyn Error>> {
+        let moves_at = args.iter().position(|&x| x == "moves").unwrap_or(args.len());
+
+        let mut game = match args.first() {
+            Some(&"startpos") => GameManager::standard(),
+            Some(&"fen") => GameManager::from_fen(&args[1..moves_at].join(" "))?,
+            _ => return Err("expected startpos or fen".into()),
+        };
+
+        for &token in args.iter().skip(moves_at + 1) {
+            game.play(Move::from_uci(token).ok_or(MoveError::InvalidNotation)?)?;
+        }
+
+        Ok(game)
+    }
+
+    fn go(&mut self, limits: GoLimits) {
+        self.stop_search();
+
+        let Some(game) = self.game.clone() else {
+            println!("info string no position set");
+            println!("bestmove 0000");
+            return;
+        };
+
+        let mut search = Search::with_table(get_search_limits(&game, limits), mem::take(&mut self.table));
+        let stop = search.get_stop_flag();
+
+        self.stop = stop.clone();
+        self.search = Some(thread::spawn(move || {
+            let result = search.run(&game, |x| println!("{}", format_info(x)));
+
+            // The best move of an infinite search is only sent once the GUI asks for it
+            while limits.infinite && !stop.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(1));
+            }
+
+            match result.best_move {
+                Some(mv) => println!("bestmove {}", mv),
+                None => println!("bestmove 0000"),
+            }
+
+            search.into_table()
+        }));
+    }
+
+    fn stop_search(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+
+        if let Some(search) = self.search.take() {
+            // A search that panicked loses its table, so start over with an empty one of the same size
+            self.table = search.join().unwrap_or_else(|_| TranspositionTable::new(self.hash_size));
+        }
+    }
+}
+
+fn get_search_limits(game: &GameManager, limits: GoLimits) -> SearchLimits {
+    let (remaining, increment) = match game.get_turn() {
+        Color::White => (limits.wtime, limits.winc),
+        Color::Black => (limits.btime, limits.binc),
+    };
+
+    // Spend a slice of the clock on each move, keeping a margin for the GUI's overhead
+    let time = match (limits.movetime, remaining) {
+        (Some(movetime), _) => Some(movetime),
+        (None, Some(remaining)) => Some((remaining / 30 + increment.unwrap_or(0) / 2).min(remaining.saturating_sub(50))),
+        (None, None) => None,
+    };
+
+    match limits.infinite {
+        true => SearchLimits::default(),
+        false => SearchLimits {
+            depth: limits.depth,
+            nodes: limits.nodes,
+            time: time.map(Duration::from_millis),
+        },
+    }
+}
+
+impl Default for Uci {
+    fn default() -> Self {
+        Self::new()
+    }
+}
\ No newline at end of file
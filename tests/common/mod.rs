@@ -0,0 +1,8 @@
+use chess::{GameManager, Move};
+
+// Plays a line of moves given in UCI notation
+pub fn play(game: &mut GameManager, moves: &str) {
+    for uci in moves.split_whitespace() {
+        game.play(Move::from_uci(uci).unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use chess::{Color, DrawReason, GameManager, GameState, MoveError};
+
+use common::play;
+
+fn assert_draw(game: &GameManager, reason: DrawReason) {
+    assert!(matches!(game.get_state(), GameState::Draw { reason: x } if *x == reason), "expected a draw by {}", reason);
+}
+
+#[test]
+fn insufficient_material() {
+    let dead = [
+        "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/1N2K3 b - - 0 1",
+        "3bk3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+        "4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1",
+    ];
+    let alive = [
+        "2b1k3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/1NB1K3 w - - 0 1",
+        "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+    ];
+
+    for fen in dead {
+        assert_draw(&GameManager::from_fen(fen).unwrap(), DrawReason::InsufficientMaterial);
+    }
+
+    for fen in alive {
+        assert!(!GameManager::from_fen(fen).unwrap().is_over(), "{}", fen);
+    }
+
+    // Taking the last pawn ends the game on the spot
+    let mut game = GameManager::from_fen("4k3/8/8/8/8/8/3p4/4K3 w - - 0 1").unwrap();
+
+    play(&mut game, "e1d2");
+    assert_draw(&game, DrawReason::InsufficientMaterial);
+}
+
+#[test]
+fn threefold_can_be_claimed_and_fivefold_ends_the_game() {
+    let mut game = GameManager::standard();
+    let shuffle = "g1f3 g8f6 f3g1 f6g8";
+
+    assert_eq!(game.claim_draw(), Err(MoveError::NoDrawToClaim));
+
+    play(&mut game, shuffle);
+    assert_eq!(game.get_repetitions(), 2);
+    assert_eq!(game.get_claimable_draw(), None);
+
+    play(&mut game, shuffle);
+    assert_eq!(game.get_claimable_draw(), Some(DrawReason::ThreefoldRepetition));
+    assert!(!game.is_over());
+
+    play(&mut game, shuffle);
+    play(&mut game, shuffle);
+    assert_draw(&game, DrawReason::FivefoldRepetition);
+
+    game.undo().unwrap();
+    assert!(!game.is_over());
+    assert_eq!(game.claim_draw(), Ok(()));
+    assert_draw(&game, DrawReason::ThreefoldRepetition);
+}
+
+#[test]
+fn fifty_and_seventy_five_move_rules() {
+    let mut game = GameManager::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 99 80").unwrap();
+
+    assert_eq!(game.get_claimable_draw(), None);
+
+    play(&mut game, "a1a2");
+    assert_eq!(game.get_claimable_draw(), Some(DrawReason::FiftyMoveRule));
+
+    let mut game = GameManager::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 149 80").unwrap();
+
+    play(&mut game, "a1a2");
+    assert_draw(&game, DrawReason::SeventyFiveMoveRule);
+
+    // Mate on the last move still counts
+    let mut game = GameManager::from_fen("6k1/8/6K1/8/8/8/8/R7 w - - 149 80").unwrap();
+
+    play(&mut game, "a1a8");
+    assert!(matches!(game.get_state(), GameState::Checkmate { .. }));
+}
+
+#[test]
+fn agreement_does_not_overturn_a_finished_game() {
+    let mut game = GameManager::standard();
+
+    game.agree_draw();
+    assert_draw(&game, DrawReason::Agreement);
+
+    let mut game = GameManager::standard();
+
+    play(&mut game, "f2f3 e7e5 g2g4 d8h4");
+    game.agree_draw();
+    assert!(matches!(game.get_state(), GameState::Checkmate { winner: Color::Black }));
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use chess::{Color, GameManager, GameState, MoveError, Square};
+
+use common::play;
+
+#[test]
+fn undo_and_redo_walk_the_history() {
+    let mut game = GameManager::standard();
+
+    assert_eq!(game.undo(), Err(MoveError::NothingToUndo));
+    assert_eq!(game.redo(), Err(MoveError::NothingToRedo));
+
+    play(&mut game, "e2e4 e7e5 g1f3");
+    let fen = game.to_fen();
+
+    game.undo().unwrap();
+    game.undo().unwrap();
+    assert_eq!(game.get_turn(), Color::Black);
+    assert!(game.can_redo());
+
+    game.redo().unwrap();
+    game.redo().unwrap();
+    assert_eq!(game.to_fen(), fen);
+    assert!(!game.can_redo());
+    assert_eq!(game.get_moves().len(), 3);
+}
+
+#[test]
+fn a_new_move_clears_the_redo_stack() {
+    let mut game = GameManager::standard();
+
+    play(&mut game, "e2e4 e7e5");
+    game.undo().unwrap();
+    play(&mut game, "c7c5");
+
+    assert!(!game.can_redo());
+    assert_eq!(game.redo(), Err(MoveError::NothingToRedo));
+}
+
+#[test]
+fn redo_keeps_its_moves_when_refused() {
+    let mut game = GameManager::standard();
+
+    play(&mut game, "e2e4 e7e5");
+    game.undo().unwrap();
+    game.undo().unwrap();
+    game.resign();
+
+    assert_eq!(game.redo(), Err(MoveError::GameOver));
+    assert!(game.can_redo());
+
+    // Taking the promotion back replays it with its piece
+    let mut game = GameManager::from_fen("8/4P3/8/8/8/8/k7/7K w - - 0 1").unwrap();
+
+    play(&mut game, "e7e8q");
+    game.undo().unwrap();
+    game.redo().unwrap();
+    assert_eq!(game.to_fen(), "4Q3/8/8/8/8/8/k7/7K b - - 0 1");
+    assert!(matches!(game.get_state(), GameState::Playing { turn: Color::Black }));
+}
+
+#[test]
+fn moving_needs_a_selected_piece() {
+    let mut game = GameManager::standard();
+
+    assert_eq!(game.move_piece(Square::E2, Square::E4), Err(MoveError::NoPieceSelected));
+
+    let mut game = GameManager::from_fen("8/4P3/8/8/8/8/k7/7K w - - 0 1").unwrap();
+
+    game.select_piece(Square::E7).unwrap();
+    game.move_piece(Square::E7, Square::E8).unwrap();
+    assert_eq!(game.move_piece(Square::E8, Square::E7), Err(MoveError::PromotionRequired));
+}
+
+#[test]
+fn an_empty_board_has_no_game_to_play() {
+    let game = GameManager::new();
+
+    assert!(matches!(game.get_state(), GameState::Stalemate));
+    assert!(game.get_legal_moves().is_empty());
+}
@@ -0,0 +1,95 @@
+use chess::{Board, Move, MoveError};
+
+fn assert_perft(fen: &str, expected: &[u64]) {
+    let mut board = Board::from_fen(fen).unwrap();
+
+    for (depth, nodes) in expected.iter().enumerate() {
+        assert_eq!(board.perft(depth as u32 + 1), *nodes, "{} at depth {}", fen, depth + 1);
+    }
+}
+
+#[test]
+fn starting_position() {
+    assert_perft("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", &[20, 400, 8902, 197281]);
+}
+
+#[test]
+fn kiwipete() {
+    assert_perft("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", &[48, 2039, 97862]);
+}
+
+#[test]
+fn position_3() {
+    assert_perft("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", &[14, 191, 2812, 43238]);
+}
+
+#[test]
+fn position_4() {
+    assert_perft("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1", &[6, 264, 9467, 422333]);
+}
+
+#[test]
+fn position_4_mirrored() {
+    assert_perft("r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1", &[6, 264, 9467]);
+}
+
+#[test]
+fn position_5() {
+    assert_perft("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8", &[44, 1486, 62379]);
+}
+
+#[test]
+fn position_6() {
+    assert_perft("r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10", &[46, 2079, 89890]);
+}
+
+#[test]
+fn divide_sums_to_perft() {
+    let mut board = Board::starting_position();
+    let divide = board.divide(3);
+
+    assert_eq!(divide.len(), 20);
+    assert_eq!(divide.iter().map(|x| x.1).sum::<u64>(), 8902);
+    assert!(divide.contains(&(Move::from_uci("e2e4").unwrap(), 600)));
+}
+
+#[test]
+fn unmake_restores_position() {
+    let fens = [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+    ];
+
+    for fen in fens {
+        let mut board = Board::from_fen(fen).unwrap();
+
+        for mv in board.get_all_playable_moves() {
+            let undo = board.make_move(mv).unwrap();
+            let hash = Board::from_fen(&board.to_fen()).unwrap().get_hash();
+
+            assert_eq!(board.get_hash(), hash, "hash after {}", mv);
+
+            board.unmake_move(&undo).unwrap();
+
+            assert_eq!(board.to_fen(), fen, "after {}", mv);
+        }
+    }
+}
+
+#[test]
+fn make_move_checks_the_promotion_piece() {
+    let fen = "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1";
+    let mut board = Board::from_fen(fen).unwrap();
+
+    assert_eq!(board.make_move(Move::from_uci("e2e4q").unwrap()).err(), Some(MoveError::InvalidPromotion));
+    assert_eq!(board.to_fen(), fen);
+
+    let fen = "8/4P3/8/8/8/8/k7/7K w - - 0 1";
+    let mut board = Board::from_fen(fen).unwrap();
+    let hash = board.get_hash();
+
+    assert_eq!(board.make_move(Move::from_uci("e7e8k").unwrap()).err(), Some(MoveError::InvalidPromotion));
+    assert_eq!(board.to_fen(), fen);
+    assert_eq!(board.get_hash(), hash);
+}
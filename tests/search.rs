@@ -0,0 +1,88 @@
+use std::sync::atomic::Ordering;
+
+use chess::{GameManager, Move, Search, SearchLimits, SearchResult, STARTING_FEN};
+
+fn search(fen: &str, limits: SearchLimits) -> (GameManager, SearchResult) {
+    let game = GameManager::from_fen(fen).unwrap();
+    let result = Search::new(limits).run(&game, |_| ());
+
+    (game, result)
+}
+
+#[test]
+fn finds_mate_in_one() {
+    let (_, result) = search("6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1", SearchLimits { depth: Some(4), ..Default::default() });
+
+    assert_eq!(result.best_move, Move::from_uci("d1d8"));
+    assert_eq!(result.get_mate_in(), Some(1));
+}
+
+#[test]
+fn finds_mate_in_two() {
+    let (mut game, result) = search("7k/8/8/8/8/8/R7/1R4K1 w - - 0 1", SearchLimits { depth: Some(5), ..Default::default() });
+
+    assert_eq!(result.get_mate_in(), Some(2));
+    assert_eq!(result.pv.len(), 3);
+
+    for mv in result.pv {
+        game.play(mv).unwrap();
+    }
+
+    assert!(game.is_over());
+}
+
+#[test]
+fn stops_at_the_node_limit() {
+    let (game, result) = search(STARTING_FEN, SearchLimits { nodes: Some(5000), ..Default::default() });
+
+    assert!(result.nodes <= 5000 + 64, "searched {} nodes", result.nodes);
+    assert!(game.get_legal_moves().contains(&result.best_move.unwrap()));
+}
+
+#[test]
+fn stops_when_the_flag_is_set() {
+    let game = GameManager::standard();
+    let mut search = Search::new(SearchLimits::default());
+
+    search.get_stop_flag().store(true, Ordering::Relaxed);
+
+    let result = search.run(&game, |_| ());
+
+    // Not even the first iteration finishes, but there is still a move to play
+    assert_eq!(result.depth, 0);
+    assert!(result.best_move.is_some());
+}
+
+#[test]
+fn table_hits_keep_the_whole_principal_variation() {
+    let game = GameManager::standard();
+    let limits = SearchLimits { depth: Some(5), ..Default::default() };
+    let mut search = Search::new(limits);
+
+    search.run(&game, |_| ());
+
+    // The second search finds most positions in the table already
+    let mut search = Search::with_table(limits, search.into_table());
+
+    search.run(&game, |x| assert_eq!(x.pv.len(), x.depth as usize, "pv at depth {}", x.depth));
+}
+
+#[test]
+fn scores_repetitions_and_the_fifty_move_rule_as_draws() {
+    // Any quiet move completes the fifty moves, and there is no mate in one
+    let (_, result) = search("4k3/8/8/8/8/8/8/R3K3 w - - 99 80", SearchLimits { depth: Some(3), ..Default::default() });
+
+    assert_eq!(result.score, 0);
+
+    // A rook down, Black can still step back into a position the game has already seen
+    let mut game = GameManager::from_fen("4k3/8/8/8/8/8/8/R3K3 b - - 0 1").unwrap();
+
+    for uci in ["e8d8", "a1a2", "d8e8", "a2a1"] {
+        game.play(Move::from_uci(uci).unwrap()).unwrap();
+    }
+
+    let result = Search::new(SearchLimits { depth: Some(3), ..Default::default() }).run(&game, |_| ());
+
+    assert_eq!(result.score, 0);
+    assert_eq!(result.best_move, Move::from_uci("e8d8"));
+}